@@ -9,14 +9,19 @@ use embedded_graphics_simulator::{
     OutputSettingsBuilder, SimulatorDisplay, Window,
 };
 use embedded_ui::{
+    action::Action,
+    app::{app, Runtime},
     col,
-    el::ElId,
+    el::{El, ElId},
     event::CommonEvent,
     helpers::{bar_h, button, container, scrollable_h, select_v},
     icons::IconKind,
+    render::DrawTargetRenderer,
     row,
-    simulator::single_encoder::Event,
-    ui::UI,
+    simulator::{
+        single_encoder::{Event, Input},
+        KeyMap,
+    },
     widgets::container::InsideContainerExt,
 };
 use embedded_ui::{helpers::bar_v, theme::Theme};
@@ -28,18 +33,24 @@ enum Message {
     KnobChange(u8),
 }
 
-fn main() {
-    let output_settings = OutputSettingsBuilder::new().scale(2).build();
-
-    let mut window = Window::new("TEST", &output_settings);
-
-    let mut display = SimulatorDisplay::<Rgb888>::new(Size::new(480, 270));
+#[derive(Default)]
+struct State {
+    knob: u8,
+}
 
-    // I don't certainly know why, but display must be drawn at least once before
-    // event fetching. Otherwise SDL2 will panic :(
-    window.update(&display);
+fn update(state: &mut State, message: Message) -> Action {
+    match message {
+        Message::Focus(id) => Action::focus(id),
+        Message::KnobChange(value) => {
+            state.knob = value;
+            Action::None
+        },
+        Message::None => Action::None,
+    }
+}
 
-    let col = row![
+fn view(_state: &State) -> El<'_, Message, DrawTargetRenderer<Rgb888>, Event, Theme<Rgb888>> {
+    row![
         col!["This is a text inside a container", button("Button").height(50), button("EDIT")].gap(1).padding(2),
         // col![text("OSC2"), button("TYPE"), button("SYNC"), button("EDIT")],
         // col![text("OSC3"), header_line, button("TYPE"), button("SYNC"), button("EDIT")],
@@ -88,24 +99,33 @@ fn main() {
             "kek"
         ]
     ]
-    .gap(1);
+    .gap(1)
+    .into()
+}
 
-    let mut ui = UI::new(col, display.bounding_box().size.into()).rgb888().theme(Theme::AyuLight);
+fn main() {
+    let output_settings = OutputSettingsBuilder::new().scale(2).build();
 
-    ui.auto_focus();
+    let mut window = Window::new("TEST", &output_settings);
 
-    loop {
-        ui.tick(window.events().filter_map(|event| Event::try_from(event).ok()));
+    let mut display = SimulatorDisplay::<Rgb888>::new(Size::new(480, 270));
 
-        while let Some(message) = ui.deque_message() {
-            match message {
-                Message::Focus(id) => ui.focus(id),
-                Message::KnobChange(_value) => {},
-                Message::None => {},
-            }
-        }
+    // I don't certainly know why, but display must be drawn at least once before
+    // event fetching. Otherwise SDL2 will panic :(
+    window.update(&display);
+
+    let mut runtime =
+        Runtime::new(app(update, view), State::default(), display.bounding_box().size.into())
+            .styler(Theme::AyuLight);
+
+    runtime.auto_focus();
+
+    let keys = KeyMap::<Input>::default();
+
+    loop {
+        runtime.tick(window.events().filter_map(|event| keys.event(event)));
 
-        ui.draw(&mut display);
+        runtime.draw(&mut display);
 
         // display
         //     .bounding_box()
@@ -0,0 +1,103 @@
+use core::time::Duration;
+
+use crate::time::Instant;
+
+/// Velocity based acceleration of encoder rotation. Each detent coming
+/// sooner than `window` after the previous one in the same direction
+/// multiplies the following ones one time more, up to `max` times. Slow
+/// rotation or direction change resets the multiplier.
+///
+/// Works only if UI has a clock, otherwise rotation is not accelerated.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(::defmt::Format))]
+pub struct Acceleration {
+    window: Duration,
+    max: u16,
+}
+
+impl Acceleration {
+    pub const fn new(window: Duration, max: u16) -> Self {
+        Self { window, max }
+    }
+}
+
+impl Default for Acceleration {
+    fn default() -> Self {
+        Self::new(Duration::from_millis(80), 8)
+    }
+}
+
+/// Rotation history of an accelerated widget, kept in its state
+#[derive(Clone, Copy, Debug, Default)]
+pub(crate) struct Velocity {
+    /// Time and direction of the last rotation
+    last: Option<(Instant, i32)>,
+    multiplier: u16,
+}
+
+impl Velocity {
+    /// Accelerated `offset`, unchanged without acceleration or clock
+    pub fn apply(
+        &mut self,
+        acceleration: Option<Acceleration>,
+        offset: i32,
+        now: Option<Instant>,
+    ) -> i32 {
+        let (Some(acceleration), Some(now)) = (acceleration, now) else {
+            return offset;
+        };
+
+        let direction = offset.signum();
+        let fast = self.last.is_some_and(|(last, last_direction)| {
+            last_direction == direction && now.duration_since(last) < acceleration.window
+        });
+
+        self.multiplier = if fast { (self.multiplier + 1).min(acceleration.max.max(1)) } else { 1 };
+        self.last = Some((now, direction));
+
+        offset.saturating_mul(self.multiplier as i32)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::time::Duration;
+
+    use crate::time::Instant;
+
+    use super::{Acceleration, Velocity};
+
+    const ACCELERATION: Option<Acceleration> =
+        Some(Acceleration::new(Duration::from_millis(50), 3));
+
+    fn rotate(velocity: &mut Velocity, offset: i32, millis: u64) -> i32 {
+        velocity.apply(ACCELERATION, offset, Some(Instant::from_millis(millis)))
+    }
+
+    #[test]
+    fn ramps_up_to_max() {
+        let mut velocity = Velocity::default();
+
+        let offsets = [0, 10, 20, 30, 40].map(|millis| rotate(&mut velocity, 1, millis));
+        assert_eq!(offsets, [1, 2, 3, 3, 3]);
+    }
+
+    #[test]
+    fn slow_or_reversed_resets() {
+        let mut velocity = Velocity::default();
+
+        rotate(&mut velocity, 1, 0);
+        assert_eq!(rotate(&mut velocity, 1, 10), 2);
+        assert_eq!(rotate(&mut velocity, 1, 100), 1);
+        assert_eq!(rotate(&mut velocity, 1, 110), 2);
+        assert_eq!(rotate(&mut velocity, -1, 120), -1);
+    }
+
+    #[test]
+    fn no_clock() {
+        let mut velocity = Velocity::default();
+
+        assert_eq!(velocity.apply(ACCELERATION, 2, None), 2);
+        assert_eq!(velocity.apply(ACCELERATION, 2, None), 2);
+    }
+}
@@ -1,14 +1,44 @@
-use crate::el::ElId;
+use embedded_graphics::primitives::Rectangle;
+
+use crate::{el::ElId, widgets::router::PageId};
+
+/// Page change of [`Router`](crate::widgets::router::Router)
+#[derive(Clone, Copy)]
+#[cfg_attr(feature = "defmt", derive(::defmt::Format))]
+pub enum Navigation {
+    /// Open page, the current one is kept to come back to it
+    Push(PageId),
+    /// Go back to the previous page
+    Pop,
+    /// Open page instead of the current one
+    Replace(PageId),
+}
 
 #[cfg_attr(feature = "defmt", derive(::defmt::Format))]
 pub enum WidgetAction {
     Focus(ElId),
+    /// Scroll parent scrollable so the element becomes visible
+    ScrollTo(ElId),
+    /// Activate widget as if it was clicked, e.g. start changing `Select`
+    /// option or `Knob` value
+    Activate(ElId),
+    Deactivate(ElId),
+    /// Reset widget state to the initial one
+    Reset(ElId),
+    /// Redraw element on the next draw, e.g. if it shows data changed
+    /// outside of the UI
+    Redraw(ElId),
+    /// Change page of the innermost router having the page, or able to go
+    /// back for [`Navigation::Pop`]
+    Navigate(Navigation),
 }
 
 #[cfg_attr(feature = "defmt", derive(::defmt::Format))]
 pub enum Action {
     None,
     Widget(WidgetAction),
+    /// Quit the UI, see `UI::on_exit`
+    Exit,
 }
 
 impl Action {
@@ -19,6 +49,46 @@ impl Action {
     pub fn focus(id: ElId) -> Self {
         Self::widget(WidgetAction::Focus(id))
     }
+
+    pub fn scroll_to(id: ElId) -> Self {
+        Self::widget(WidgetAction::ScrollTo(id))
+    }
+
+    pub fn activate(id: ElId) -> Self {
+        Self::widget(WidgetAction::Activate(id))
+    }
+
+    pub fn deactivate(id: ElId) -> Self {
+        Self::widget(WidgetAction::Deactivate(id))
+    }
+
+    pub fn reset(id: ElId) -> Self {
+        Self::widget(WidgetAction::Reset(id))
+    }
+
+    pub fn redraw(id: ElId) -> Self {
+        Self::widget(WidgetAction::Redraw(id))
+    }
+
+    pub fn navigate(navigation: Navigation) -> Self {
+        Self::widget(WidgetAction::Navigate(navigation))
+    }
+
+    pub fn push(page: impl Into<PageId>) -> Self {
+        Self::navigate(Navigation::Push(page.into()))
+    }
+
+    pub fn pop() -> Self {
+        Self::navigate(Navigation::Pop)
+    }
+
+    pub fn replace(page: impl Into<PageId>) -> Self {
+        Self::navigate(Navigation::Replace(page.into()))
+    }
+
+    pub fn exit() -> Self {
+        Self::Exit
+    }
 }
 
 impl From<()> for Action {
@@ -26,3 +96,21 @@ impl From<()> for Action {
         Self::None
     }
 }
+
+impl From<WidgetAction> for Action {
+    fn from(value: WidgetAction) -> Self {
+        Self::Widget(value)
+    }
+}
+
+/// Result of [`WidgetAction`] passed down the widget tree
+pub enum ActionResult {
+    /// Action does not target the widget or any of its children
+    Ignored,
+    /// Action is performed
+    Done,
+    /// Action targets the widget but must be performed by one of its parents,
+    /// e.g. [`WidgetAction::ScrollTo`]. Contains bounds of the target in
+    /// viewport.
+    Found(Rectangle),
+}
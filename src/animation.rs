@@ -0,0 +1,269 @@
+use core::{fmt, time::Duration};
+
+use embedded_graphics::{
+    geometry::Point,
+    pixelcolor::{BinaryColor, Rgb555, Rgb565, Rgb666, Rgb888, RgbColor},
+};
+
+use crate::time::Instant;
+
+/// Easing curve mapping linear progress in `0.0..=1.0` to animation progress
+#[derive(Clone, Copy)]
+pub enum Easing {
+    Linear,
+    EaseIn,
+    EaseOut,
+    EaseInOut,
+    Custom(fn(f32) -> f32),
+}
+
+impl Easing {
+    pub fn apply(self, t: f32) -> f32 {
+        let t = t.clamp(0.0, 1.0);
+
+        match self {
+            Easing::Linear => t,
+            Easing::EaseIn => t * t * t,
+            Easing::EaseOut => {
+                let inv = 1.0 - t;
+                1.0 - inv * inv * inv
+            },
+            Easing::EaseInOut => {
+                if t < 0.5 {
+                    4.0 * t * t * t
+                } else {
+                    let inv = -2.0 * t + 2.0;
+                    1.0 - inv * inv * inv / 2.0
+                }
+            },
+            Easing::Custom(f) => f(t),
+        }
+    }
+}
+
+/// Values which can be animated
+pub trait Interpolate: Copy + PartialEq {
+    /// Value between `self` (`t = 0.0`) and `to` (`t = 1.0`)
+    fn lerp(self, to: Self, t: f32) -> Self;
+}
+
+impl Interpolate for f32 {
+    fn lerp(self, to: Self, t: f32) -> Self {
+        self + (to - self) * t
+    }
+}
+
+macro_rules! interpolate_ints {
+    ($($ty: ty),*) => {
+        $(
+            impl Interpolate for $ty {
+                fn lerp(self, to: Self, t: f32) -> Self {
+                    (self as f32).lerp(to as f32, t) as $ty
+                }
+            }
+        )*
+    };
+}
+
+interpolate_ints!(u8, u32, i32);
+
+impl Interpolate for Point {
+    fn lerp(self, to: Self, t: f32) -> Self {
+        Point::new(self.x.lerp(to.x, t), self.y.lerp(to.y, t))
+    }
+}
+
+impl Interpolate for BinaryColor {
+    fn lerp(self, to: Self, t: f32) -> Self {
+        if t < 0.5 {
+            self
+        } else {
+            to
+        }
+    }
+}
+
+macro_rules! interpolate_rgb {
+    ($($ty: ty),*) => {
+        $(
+            impl Interpolate for $ty {
+                fn lerp(self, to: Self, t: f32) -> Self {
+                    <$ty>::new(
+                        self.r().lerp(to.r(), t),
+                        self.g().lerp(to.g(), t),
+                        self.b().lerp(to.b(), t),
+                    )
+                }
+            }
+        )*
+    };
+}
+
+interpolate_rgb!(Rgb555, Rgb565, Rgb666, Rgb888);
+
+/// How a value changes to a new one
+#[derive(Clone, Copy)]
+pub struct Transition {
+    pub duration: Duration,
+    pub easing: Easing,
+}
+
+impl Transition {
+    pub fn new(duration: Duration) -> Self {
+        Self { duration, easing: Easing::EaseOut }
+    }
+
+    /// Change value instantly
+    pub fn none() -> Self {
+        Self::new(Duration::ZERO)
+    }
+
+    pub fn easing(mut self, easing: Easing) -> Self {
+        self.easing = easing;
+        self
+    }
+}
+
+impl Default for Transition {
+    fn default() -> Self {
+        Self::none()
+    }
+}
+
+impl From<Duration> for Transition {
+    fn from(value: Duration) -> Self {
+        Self::new(value)
+    }
+}
+
+/// Single animation from one value to another
+#[derive(Clone, Copy)]
+pub struct Tween<T> {
+    from: T,
+    to: T,
+    start: Instant,
+    transition: Transition,
+}
+
+impl<T: Interpolate> Tween<T> {
+    pub fn new(from: T, to: T, start: Instant, transition: Transition) -> Self {
+        Self { from, to, start, transition }
+    }
+
+    pub fn target(&self) -> T {
+        self.to
+    }
+
+    pub fn progress(&self, now: Instant) -> f32 {
+        if self.transition.duration.is_zero() {
+            return 1.0;
+        }
+
+        let passed = now.duration_since(self.start).as_millis() as f32;
+        (passed / self.transition.duration.as_millis() as f32).min(1.0)
+    }
+
+    pub fn is_done(&self, now: Instant) -> bool {
+        self.progress(now) >= 1.0
+    }
+
+    pub fn value(&self, now: Instant) -> T {
+        let progress = self.progress(now);
+
+        if progress >= 1.0 {
+            self.to
+        } else {
+            self.from.lerp(self.to, self.transition.easing.apply(progress))
+        }
+    }
+}
+
+/// Value smoothly following its target. Kept in widget state.
+#[derive(Clone, Copy)]
+pub struct Animated<T> {
+    tween: Tween<T>,
+}
+
+impl<T: Interpolate> Animated<T> {
+    pub fn new(value: T) -> Self {
+        Self { tween: Tween::new(value, value, Instant::ZERO, Transition::none()) }
+    }
+
+    /// Start transition to `target` from the current value, if target
+    /// changed
+    pub fn set(&mut self, target: T, transition: Transition, now: Instant) {
+        if target != self.tween.target() {
+            self.tween = Tween::new(self.value(now), target, now, transition);
+        }
+    }
+
+    pub fn value(&self, now: Instant) -> T {
+        self.tween.value(now)
+    }
+
+    pub fn is_running(&self, now: Instant) -> bool {
+        !self.tween.is_done(now)
+    }
+}
+
+/// Shows only the target, time of the running transition is not
+/// interesting in state dumps
+impl<T: fmt::Debug> fmt::Debug for Animated<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Animated").field(&self.tween.to).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::time::Duration;
+
+    use crate::time::{Clock, Instant, ManualClock};
+
+    use super::{Animated, Easing, Transition, Tween};
+
+    #[test]
+    fn easing_keeps_ends() {
+        for easing in [Easing::Linear, Easing::EaseIn, Easing::EaseOut, Easing::EaseInOut] {
+            assert_eq!(easing.apply(0.0), 0.0);
+            assert_eq!(easing.apply(1.0), 1.0);
+        }
+    }
+
+    #[test]
+    fn tween_follows_clock() {
+        let clock = ManualClock::default();
+        let tween = Tween::new(
+            0u32,
+            100,
+            clock.now(),
+            Transition::new(Duration::from_millis(100)).easing(Easing::Linear),
+        );
+
+        assert_eq!(tween.value(clock.now()), 0);
+        clock.advance(Duration::from_millis(25));
+        assert_eq!(tween.value(clock.now()), 25);
+        clock.advance(Duration::from_millis(100));
+        assert_eq!(tween.value(clock.now()), 100);
+        assert!(tween.is_done(clock.now()));
+    }
+
+    #[test]
+    fn retargeting_starts_from_current_value() {
+        let clock = ManualClock::new(Instant::from_millis(1000));
+        let transition = Transition::new(Duration::from_millis(100)).easing(Easing::Linear);
+        let mut animated = Animated::new(0.0f32);
+
+        animated.set(100.0, transition, clock.now());
+        clock.advance(Duration::from_millis(50));
+        assert_eq!(animated.value(clock.now()), 50.0);
+
+        animated.set(0.0, transition, clock.now());
+        clock.advance(Duration::from_millis(50));
+        assert_eq!(animated.value(clock.now()), 25.0);
+        assert!(animated.is_running(clock.now()));
+
+        clock.advance(Duration::from_millis(50));
+        assert!(!animated.is_running(clock.now()));
+    }
+}
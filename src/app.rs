@@ -1,68 +1,409 @@
-// use core::marker::PhantomData;
-
-// use crate::{action::Action, el::El, event::Event, render::Renderer};
-
-// pub trait App<'a> {
-//     type State;
-//     type Message;
-//     type R: Renderer;
-//     type E: Event;
-//     type S;
-
-//     fn update(&self, state: &mut Self::State, message: Self::Message) -> impl Into<Action>;
-
-//     fn view(
-//         &self,
-//         state: &'a Self::State,
-//     ) -> impl Into<El<'a, Self::Message, Self::R, Self::E, Self::S>>;
-// }
-
-// pub fn app<'a, State, Message, R: Renderer, E: Event, S>(
-//     update: impl Fn(&mut State, Message) -> Action,
-//     view: impl Fn(&State),
-// ) -> impl App<'a> {
-//     struct Instance<State, Message, R: Renderer, E: Event, S, U, V> {
-//         update: U,
-//         view: V,
-//         _state: PhantomData<State>,
-//         _message: PhantomData<Message>,
-//         _r: PhantomData<R>,
-//         _e: PhantomData<E>,
-//         _s: PhantomData<S>,
-//     }
-
-//     impl<'a, State, Message, R, E, S, U, V> App<'a> for Instance<State, Message, R, E, S, U, V>
-//     where
-//         R: Renderer,
-//         E: Event,
-//         U: Fn(&mut State, Message),
-//         V: Fn(&State) -> Into<El<'a, Self::Message, Self::R, Self::E, Self::S>>,
-//     {
-//         type State = State;
-//         type Message = Message;
-//         type R = R;
-//         type E = E;
-//         type S = S;
-
-//         fn update(&self, state: &mut Self::State, message: Self::Message) -> impl Into<Action> {
-//             (self.update)(state, message)
-//         }
-
-//         fn view(
-//             &self,
-//             state: &'a Self::State,
-//         ) -> impl Into<El<'a, Self::Message, Self::R, Self::E, Self::S>> {
-//             (self.view)(state)
-//         }
-//     }
-
-//     Instance {
-//         update,
-//         view,
-//         _state: PhantomData,
-//         _message: PhantomData,
-//         _r: PhantomData,
-//         _e: PhantomData,
-//         _s: PhantomData,
-//     }
-// }
+use core::{marker::PhantomData, ptr::NonNull};
+
+use alloc::{boxed::Box, vec::Vec};
+use embedded_graphics::draw_target::DrawTarget;
+
+use crate::{
+    action::Action,
+    collections::Subscriptions,
+    color::UiColor,
+    el::{El, ElId},
+    event::Event,
+    render::{DrawTargetRenderer, Renderer},
+    size::Size,
+    style::Styler,
+    subscription::Subscription,
+    time::Instant,
+    ui::{UiCache, UI},
+};
+
+/// Elm-like application: state is changed only by `update` and the whole
+/// element tree is rebuilt from it by `view`. Types are `'static` as
+/// [`Runtime`] keeps the view built from the state it owns.
+pub trait App {
+    type State: 'static;
+    type Message: 'static;
+    type R: Renderer + 'static;
+    type E: Event + 'static;
+    type S: Styler<<Self::R as Renderer>::Color> + 'static;
+
+    fn update(&self, state: &mut Self::State, message: Self::Message) -> Action;
+
+    fn view<'a>(&self, state: &'a Self::State) -> El<'a, Self::Message, Self::R, Self::E, Self::S>;
+
+    /// Timers active in the given state, declared again after each `update`
+    fn subscription(&self, _state: &Self::State) -> Subscriptions<Subscription<Self::Message>> {
+        Subscriptions::new()
+    }
+}
+
+type SubscriptionFn<State, Message> = fn(&State) -> Subscriptions<Subscription<Message>>;
+
+pub struct Instance<State, Message, R, E, S, A, U, V> {
+    update: U,
+    view: V,
+    subscription: Option<SubscriptionFn<State, Message>>,
+    marker: PhantomData<(State, Message, R, E, S, A)>,
+}
+
+impl<State, Message, R, E, S, A, U, V> App for Instance<State, Message, R, E, S, A, U, V>
+where
+    State: 'static,
+    Message: 'static,
+    R: Renderer + 'static,
+    E: Event + 'static,
+    S: Styler<R::Color> + 'static,
+    A: Into<Action>,
+    U: Fn(&mut State, Message) -> A,
+    V: for<'a> Fn(&'a State) -> El<'a, Message, R, E, S>,
+{
+    type State = State;
+    type Message = Message;
+    type R = R;
+    type E = E;
+    type S = S;
+
+    fn update(&self, state: &mut Self::State, message: Self::Message) -> Action {
+        (self.update)(state, message).into()
+    }
+
+    fn view<'a>(&self, state: &'a Self::State) -> El<'a, Self::Message, Self::R, Self::E, Self::S> {
+        (self.view)(state)
+    }
+
+    fn subscription(&self, state: &Self::State) -> Subscriptions<Subscription<Self::Message>> {
+        self.subscription.map(|subscription| subscription(state)).unwrap_or_default()
+    }
+}
+
+impl<State, Message, R, E, S, A, U, V> Instance<State, Message, R, E, S, A, U, V> {
+    /// Declare timers active in the given state, see [`App::subscription`]
+    pub fn subscription(mut self, subscription: SubscriptionFn<State, Message>) -> Self {
+        self.subscription = Some(subscription);
+        self
+    }
+}
+
+/// Build an [`App`] from `update` and `view` functions
+pub fn app<State, Message, R, E, S, A, U, V>(
+    update: U,
+    view: V,
+) -> Instance<State, Message, R, E, S, A, U, V>
+where
+    R: Renderer,
+    E: Event,
+    S: Styler<R::Color>,
+    A: Into<Action>,
+    U: Fn(&mut State, Message) -> A,
+    V: for<'a> Fn(&'a State) -> El<'a, Message, R, E, S>,
+{
+    Instance { update, view, subscription: None, marker: PhantomData }
+}
+
+type AppUi<A> = UI<'static, <A as App>::Message, <A as App>::R, <A as App>::E, <A as App>::S>;
+
+/// Runs an [`App`]: feeds events to the view built from current state,
+/// passes published messages to `update` and rebuilds the view, keeping
+/// widget states between rebuilds.
+///
+/// Built UI is kept while the state doesn't change, so ticks without
+/// messages and draws don't rebuild and relayout the view. The view is
+/// rebuilt only after `update`, i.e. when widgets published messages or a
+/// subscription fired.
+pub struct Runtime<A: App> {
+    app: A,
+    /// UI built from `state`, borrowing it. Set when `cache` is not.
+    ui: Option<AppUi<A>>,
+    cache: Option<UiCache<A::Message, A::S>>,
+    /// Leaked box, so it doesn't move with the runtime and its uniqueness is
+    /// not asserted while `ui` borrows it. Freed on drop.
+    state: NonNull<A::State>,
+}
+
+impl<A: App> Runtime<A> {
+    pub fn new(app: A, state: A::State, viewport_size: Size) -> Self {
+        let mut cache = UiCache::new(viewport_size);
+        cache.subscribe(app.subscription(&state));
+
+        Self { app, ui: None, cache: Some(cache), state: NonNull::from(Box::leak(Box::new(state))) }
+    }
+
+    pub fn styler(mut self, styler: A::S) -> Self {
+        self.cache = self.take_cache().map(|cache| cache.styler(styler));
+        self
+    }
+
+    pub fn on_exit(mut self, f: impl FnOnce() + 'static) -> Self {
+        self.cache = self.take_cache().map(|cache| cache.on_exit(f));
+        self
+    }
+
+    /// Time source for animations and subscriptions
+    pub fn clock(mut self, clock: impl crate::time::Clock + 'static) -> Self {
+        self.cache = self.take_cache().map(|cache| cache.clock(clock));
+        self
+    }
+
+    /// Move focus from the last element to the first one and back
+    pub fn wrap_focus(mut self) -> Self {
+        self.cache = self.take_cache().map(|cache| cache.wrap_focus());
+        self
+    }
+
+    /// Timings of focus button gestures, see [`UI::gestures`]
+    pub fn gestures(mut self, gestures: crate::gesture::Gestures) -> Self {
+        self.cache = self.take_cache().map(|cache| cache.gestures(gestures));
+        self
+    }
+
+    /// Start in debug mode, see [`UI::debug`]
+    pub fn debug(mut self, debug: bool) -> Self {
+        self.cache = self.take_cache().map(|cache| cache.debug(debug));
+        self
+    }
+
+    pub fn state(&self) -> &A::State {
+        // SAFETY: State is valid until drop and mutated only in `tick` through
+        // `&mut self`, so not while this borrow lives
+        unsafe { self.state.as_ref() }
+    }
+
+    /// The earliest time some subscription fires or a gesture is recognized
+    /// at, see [`UI::next_deadline`]
+    pub fn next_deadline(&self) -> Option<Instant> {
+        match (&self.ui, &self.cache) {
+            (Some(ui), _) => ui.next_deadline(),
+            (None, cache) => cache.as_ref().and_then(|cache| cache.next_deadline()),
+        }
+    }
+
+    /// Change viewport size, e.g. on display rotation
+    pub fn resize(&mut self, viewport_size: Size) {
+        self.ui().resize(viewport_size);
+    }
+
+    pub fn auto_focus(&mut self) {
+        self.ui().auto_focus();
+    }
+
+    pub fn tick(&mut self, events: impl Iterator<Item = A::E>) {
+        let ui = self.ui();
+        ui.tick(events);
+        let messages = core::iter::from_fn(|| ui.deque_message()).collect::<Vec<_>>();
+
+        if messages.is_empty() {
+            return;
+        }
+
+        // UI borrowing the state must be dropped before `update` changes it
+        self.cache = self.take_cache();
+
+        // SAFETY: No UI borrows the state anymore and `&mut self` is held, so
+        // this is the only reference to it
+        let state = unsafe { self.state.as_mut() };
+        let actions =
+            messages.into_iter().map(|message| self.app.update(state, message)).collect::<Vec<_>>();
+
+        let subscriptions = self.app.subscription(state);
+
+        let ui = self.ui();
+        // Any element may look different after `update`
+        ui.redraw();
+        for action in actions {
+            ui.perform(action);
+        }
+        ui.subscribe(subscriptions);
+    }
+
+    /// Built UI, building it from the state if it was dropped
+    fn ui(&mut self) -> &mut AppUi<A> {
+        if self.ui.is_none() {
+            let cache = self.cache.take().expect("Either UI or its cache is kept");
+
+            // SAFETY: State outlives the UI: UI is dropped before the state is
+            // changed in `tick` and before it is freed on drop. The `'static`
+            // lifetime doesn't leave the runtime.
+            let state: &'static A::State = unsafe { self.state.as_ref() };
+            let app = &self.app;
+            self.ui = Some(UI::with_cache(ElId::scope(|| app.view(state)), cache));
+        }
+
+        self.ui.as_mut().unwrap()
+    }
+
+    /// Drop the built UI, keeping everything needed to rebuild it
+    fn take_cache(&mut self) -> Option<UiCache<A::Message, A::S>> {
+        match self.ui.take() {
+            Some(ui) => Some(ui.into_cache()),
+            None => self.cache.take(),
+        }
+    }
+}
+
+impl<A: App> Drop for Runtime<A> {
+    fn drop(&mut self) {
+        // UI borrows the state, so it goes first
+        self.ui = None;
+
+        // SAFETY: State was leaked from a box in `new`, nothing borrows it
+        drop(unsafe { Box::from_raw(self.state.as_ptr()) });
+    }
+}
+
+impl<A, C> Runtime<A>
+where
+    A: App<R = DrawTargetRenderer<C>>,
+    C: UiColor + 'static,
+{
+    pub fn draw<D>(&mut self, target: &mut D)
+    where
+        D: DrawTarget<Color = C>,
+        D::Error: core::fmt::Debug,
+    {
+        self.ui().draw(target);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::{rc::Rc, vec::Vec};
+    use core::{cell::Cell, time::Duration};
+
+    use embedded_graphics::pixelcolor::Rgb888;
+
+    use crate::{
+        action::Action,
+        col,
+        collections::Subscriptions,
+        el::{El, ElId},
+        event::CommonEvent,
+        helpers::{button, text},
+        render::DrawTargetRenderer,
+        size::Size,
+        subscription::Subscription,
+        testing::{click, Event, Framebuffer},
+        theme::Theme,
+        time::{Instant, ManualClock},
+    };
+
+    use super::{app, Runtime};
+
+    #[derive(Clone, Debug)]
+    enum Message {
+        Add,
+        Quit,
+    }
+
+    type View<'a> = El<'a, Message, DrawTargetRenderer<Rgb888>, Event, Theme<Rgb888>>;
+
+    fn update(count: &mut u32, message: Message) -> Action {
+        match message {
+            Message::Add => {
+                *count += 1;
+                Action::None
+            },
+            Message::Quit => Action::exit(),
+        }
+    }
+
+    fn view(count: &u32) -> View<'_> {
+        col![button(text(*count)).on_press(Message::Add), button("Quit").on_press(Message::Quit)]
+            .into()
+    }
+
+    fn frame(
+        runtime: &mut Runtime<impl super::App<R = DrawTargetRenderer<Rgb888>>>,
+    ) -> Vec<Rgb888> {
+        let mut frame =
+            Framebuffer::new(embedded_graphics::geometry::Size::new(64, 32), Rgb888::default());
+        runtime.draw(&mut frame);
+        frame.pixels().to_vec()
+    }
+
+    #[test]
+    fn updates_state_and_rebuilds_view() {
+        let mut runtime = Runtime::new(app(update, view), 0, Size::new(64, 32));
+        runtime.auto_focus();
+        let before = frame(&mut runtime);
+
+        runtime.tick(click().into_iter());
+        assert_eq!(*runtime.state(), 1);
+        assert_ne!(frame(&mut runtime), before);
+
+        // Focus survives the rebuild, so the same button is clicked again
+        runtime.tick(click().into_iter());
+        assert_eq!(*runtime.state(), 2);
+    }
+
+    #[test]
+    fn rebuilds_view_only_after_update() {
+        let builds = Rc::new(Cell::new(0));
+        let counter = builds.clone();
+        let counted = app(update, move |count| {
+            counter.set(counter.get() + 1);
+            view(count)
+        });
+        let mut runtime = Runtime::new(counted, 0, Size::new(64, 32));
+        runtime.auto_focus();
+        frame(&mut runtime);
+
+        // Nothing published, the built view is kept
+        runtime.tick([CommonEvent::FocusButtonDown.into()].into_iter());
+        frame(&mut runtime);
+        assert_eq!(builds.get(), 1);
+
+        runtime.tick([CommonEvent::FocusButtonUp.into()].into_iter());
+        frame(&mut runtime);
+        assert_eq!(builds.get(), 2);
+    }
+
+    #[test]
+    fn performs_actions_of_update() {
+        let exited = Rc::new(Cell::new(false));
+        let on_exit = exited.clone();
+        let mut runtime = Runtime::new(app(update, view), 0, Size::new(64, 32))
+            .on_exit(move || on_exit.set(true));
+        runtime.auto_focus();
+
+        runtime.tick([CommonEvent::FocusMove(1).into()].into_iter());
+        runtime.tick(click().into_iter());
+
+        assert!(exited.get());
+        assert_eq!(*runtime.state(), 0);
+    }
+
+    #[test]
+    fn declares_subscriptions_of_state() {
+        let clock = Rc::new(ManualClock::new(Instant::ZERO));
+        let adder = app(update, view).subscription(|&count| match count {
+            0 => [Subscription::timeout("add", Duration::from_millis(100), Message::Add)]
+                .into_iter()
+                .collect(),
+            _ => Subscriptions::new(),
+        });
+        let mut runtime = Runtime::new(adder, 0, Size::new(64, 32)).clock(clock.clone());
+
+        runtime.tick(core::iter::empty());
+        clock.advance(Duration::from_millis(100));
+        runtime.tick(core::iter::empty());
+        assert_eq!(*runtime.state(), 1);
+
+        // No timer in the new state
+        clock.advance(Duration::from_millis(100));
+        runtime.tick(core::iter::empty());
+        assert_eq!(runtime.next_deadline(), None);
+        assert_eq!(*runtime.state(), 1);
+    }
+
+    #[test]
+    fn scoped_ids_restart_from_zero() {
+        let outer = ElId::unique();
+        let build = || [ElId::unique(), ElId::unique()];
+
+        assert_eq!(ElId::scope(build), [ElId::Unique(0), ElId::Unique(1)]);
+        assert_eq!(ElId::scope(build), ElId::scope(build));
+
+        // Global counter is not reset by scopes
+        assert_ne!(ElId::unique(), outer);
+    }
+}
@@ -0,0 +1,187 @@
+//! Collections which grow with the view or with input. With `heapless`
+//! feature these are fixed-capacity [`heapless`] collections:
+//! - Children of containers, capacity is a const parameter of the widget,
+//!   [`DEFAULT_CAPACITY`] unless the widget is built with `with_capacity`,
+//!   e.g. `Column::<_, _, _, _, 32>::with_capacity(children)`
+//! - Messages published during a single tick, see [`MAX_MESSAGES`]
+//! - Active subscriptions, see [`MAX_SUBSCRIPTIONS`]
+//!
+//! Build containers with `try_*` methods, e.g. `Linear::try_add`, to handle
+//! overflow. Chainable builders, e.g. `Linear::add`, panic when the widget
+//! is full, with `heapless-truncate` feature they drop the child with a
+//! warning instead.
+//!
+//! The feature bounds these collections only, it does not make the crate
+//! allocator-free. Global allocator is still required for what is built
+//! once per view rebuild:
+//! - `StateNode` and `LayoutNode` trees, recursive nodes cannot be stored
+//!   inline in fixed-capacity vectors
+//! - `State` of widgets bigger than `state::INLINE_SIZE`, which is boxed,
+//!   e.g. history of `Router`
+//! - Elements built with `El::new`, use `El::borrowed` to avoid boxing
+//! - Event handlers of widgets, e.g. `Checkbox::on_change`
+//! - `DrawTargetRenderer` canvas
+
+/// Capacity of children collections when the widget doesn't set it
+pub const DEFAULT_CAPACITY: usize = 16;
+
+/// Maximum count of messages published during a single tick with
+/// `heapless` feature
+pub const MAX_MESSAGES: usize = 32;
+
+/// Maximum count of active subscriptions with `heapless` feature
+pub const MAX_SUBSCRIPTIONS: usize = 8;
+
+#[cfg(feature = "heapless")]
+pub type Children<T, const N: usize = DEFAULT_CAPACITY> = heapless::Vec<T, N>;
+
+/// Without `heapless` feature the capacity is not limited
+#[cfg(not(feature = "heapless"))]
+pub type Children<T, const N: usize = DEFAULT_CAPACITY> = alloc::vec::Vec<T>;
+
+#[cfg(feature = "heapless")]
+pub type MessageQueue<T> = heapless::Deque<T, MAX_MESSAGES>;
+
+#[cfg(not(feature = "heapless"))]
+pub type MessageQueue<T> = alloc::collections::VecDeque<T>;
+
+#[cfg(feature = "heapless")]
+pub type Subscriptions<T> = heapless::Vec<T, MAX_SUBSCRIPTIONS>;
+
+#[cfg(not(feature = "heapless"))]
+pub type Subscriptions<T> = alloc::vec::Vec<T>;
+
+/// Children don't fit into the collection
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(::defmt::Format))]
+pub struct CapacityError {
+    pub capacity: usize,
+}
+
+/// Push to [`Children`], giving the child back if it doesn't fit
+pub fn push<T, const N: usize>(children: &mut Children<T, N>, child: T) -> Result<(), T> {
+    #[cfg(feature = "heapless")]
+    return children.push(child);
+
+    #[cfg(not(feature = "heapless"))]
+    {
+        children.push(child);
+        Ok(())
+    }
+}
+
+/// Collect [`Children`], failing if there are more of them than fit
+pub fn collect<T, const N: usize>(
+    children: impl IntoIterator<Item = T>,
+) -> Result<Children<T, N>, CapacityError> {
+    let mut collected = Children::new();
+    for child in children {
+        push::<_, N>(&mut collected, child).map_err(|_| CapacityError { capacity: N })?;
+    }
+
+    Ok(collected)
+}
+
+/// Collect [`Children`] for a chainable builder, see [`push_or_overflow`]
+pub(crate) fn collect_or_overflow<T, const N: usize>(
+    children: impl IntoIterator<Item = T>,
+) -> Children<T, N> {
+    let mut collected = Children::new();
+    for child in children {
+        push_or_overflow::<_, N>(&mut collected, child);
+    }
+
+    collected
+}
+
+/// Push to [`Children`] for a chainable builder. Panics if the child
+/// doesn't fit, with `heapless-truncate` feature drops it with a warning.
+pub(crate) fn push_or_overflow<T, const N: usize>(children: &mut Children<T, N>, child: T) {
+    if push::<_, N>(children, child).is_err() {
+        #[cfg(feature = "heapless-truncate")]
+        crate::log::logger::warning!("Child dropped, capacity of {} children is full", N);
+
+        #[cfg(not(feature = "heapless-truncate"))]
+        panic!("Capacity of {} children is full, use `try_*` builders to handle it", N);
+    }
+}
+
+/// Push a subscription, panics if there are more than [`MAX_SUBSCRIPTIONS`]
+pub(crate) fn push_subscription<T>(subscriptions: &mut Subscriptions<T>, subscription: T) {
+    #[cfg(feature = "heapless")]
+    if subscriptions.push(subscription).is_err() {
+        panic!("Too many subscriptions, the limit is {}", MAX_SUBSCRIPTIONS);
+    }
+
+    #[cfg(not(feature = "heapless"))]
+    subscriptions.push(subscription);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{collect, push, Children};
+
+    #[test]
+    fn pushes_children() {
+        let mut children: Children<u8, 2> = collect::<_, 2>([1, 2]).unwrap();
+        assert_eq!(children.len(), 2);
+
+        #[cfg(not(feature = "heapless"))]
+        assert_eq!(push::<_, 2>(&mut children, 3), Ok(()));
+
+        #[cfg(feature = "heapless")]
+        assert_eq!(push::<_, 2>(&mut children, 3), Err(3));
+    }
+
+    #[cfg(feature = "heapless")]
+    #[test]
+    fn overflow_is_an_error() {
+        use embedded_graphics::pixelcolor::Rgb888;
+
+        use super::CapacityError;
+        use crate::{
+            helpers::text, render::DrawTargetRenderer, testing::Event, theme::Theme,
+            widgets::linear::Column,
+        };
+
+        type Col = Column<'static, (), DrawTargetRenderer<Rgb888>, Event, Theme<Rgb888>, 2>;
+
+        assert_eq!(collect::<u8, 2>([1, 2, 3]).unwrap_err(), CapacityError { capacity: 2 });
+
+        let column = Col::with_capacity([]).try_add(text("a")).and_then(|col| col.try_add("b"));
+        assert!(column.is_ok());
+        assert_eq!(column.unwrap().try_add("c").err(), Some(CapacityError { capacity: 2 }));
+    }
+
+    #[cfg(all(feature = "heapless", not(feature = "heapless-truncate")))]
+    #[test]
+    #[should_panic = "Capacity of 2 children is full"]
+    fn builder_panics_on_overflow() {
+        use embedded_graphics::pixelcolor::Rgb888;
+
+        use crate::{
+            render::DrawTargetRenderer, testing::Event, theme::Theme, widgets::linear::Column,
+        };
+
+        type Col = Column<'static, (), DrawTargetRenderer<Rgb888>, Event, Theme<Rgb888>, 2>;
+
+        let _ = Col::with_capacity(["a".into(), "b".into()]).add("c");
+    }
+
+    #[cfg(feature = "heapless-truncate")]
+    #[test]
+    fn builder_drops_overflowing_child() {
+        use embedded_graphics::pixelcolor::Rgb888;
+
+        use super::CapacityError;
+        use crate::{
+            render::DrawTargetRenderer, testing::Event, theme::Theme, widgets::linear::Column,
+        };
+
+        type Col = Column<'static, (), DrawTargetRenderer<Rgb888>, Event, Theme<Rgb888>, 2>;
+
+        // Builder stays chainable, dropping the child
+        let column = Col::with_capacity(["a".into(), "b".into(), "c".into()]).add("d");
+        assert_eq!(column.try_add("e").err(), Some(CapacityError { capacity: 2 }));
+    }
+}
@@ -1,19 +1,254 @@
+use alloc::{format, string::String, vec::Vec};
+use core::fmt;
+use embedded_graphics::primitives::Rectangle;
+
 use crate::{
-    block::{Block, BoxModel},
+    block::{Block, Border},
     color::UiColor,
+    el::ElId,
+    event::{CommonEvent, Event},
+    layout::{Layout, Viewport},
+    render::Renderer,
+    size::{Length, Size},
+    state::{State, StateNode, StateTag},
+    widget::Widget,
 };
 
+/// What debug mode draws and [`UI::dump_tree`](crate::ui::UI::dump_tree)
+/// prints for a widget, see [`Widget::debug`]
 pub struct WidgetDebug<C: UiColor> {
+    /// Widget type name without path and generics
+    pub name: &'static str,
+    pub id: Option<ElId>,
+    /// Size widget requests from its parent
+    pub size: Size<Length>,
+    pub state_tag: StateTag,
+    /// State printed with `Debug`, `None` if widget is stateless
+    pub state: Option<String>,
+    /// Additional block widget wants to show, e.g. its hit area
     pub block: Option<Block<C>>,
+    pub bounds: Rectangle,
+    /// Bounds without padding
+    pub content: Rectangle,
+    pub children: Vec<WidgetDebug<C>>,
 }
 
 impl<C: UiColor> WidgetDebug<C> {
-    pub fn new() -> Self {
-        Self { block: None }
+    /// Info of the widget itself, without children
+    pub fn new<Message, R, E, S>(
+        widget: &(impl Widget<Message, R, E, S> + ?Sized),
+        state: &StateNode,
+        layout: Layout,
+        viewport: &Viewport,
+    ) -> Self
+    where
+        R: Renderer<Color = C>,
+        E: Event,
+    {
+        Self {
+            name: short_type_name(core::any::type_name_of_val(widget)),
+            id: widget.id(),
+            size: widget.size(viewport),
+            state_tag: state.tag,
+            state: match &state.state {
+                State::None => None,
+                state => Some(format!("{state:?}")),
+            },
+            block: None,
+            bounds: layout.bounds(),
+            content: layout.content_bounds(),
+            children: Vec::new(),
+        }
     }
 
     pub fn block(mut self, block: Block<C>) -> Self {
         self.block = Some(block);
         self
     }
+
+    /// Containers pass debug boxes of their children
+    pub fn children(mut self, children: impl IntoIterator<Item = Option<WidgetDebug<C>>>) -> Self {
+        self.children = children.into_iter().flatten().collect();
+        self
+    }
+
+    /// Draw outlines of bounds and content boxes of the whole subtree
+    pub fn draw<R: Renderer<Color = C>>(&self, renderer: &mut R) {
+        outline(renderer, self.bounds, C::from_rgb(0xff, 0x00, 0xff), 1);
+        if self.content != self.bounds {
+            outline(renderer, self.content, C::from_rgb(0x00, 0xbf, 0xff), 1);
+        }
+
+        if let Some(block) = self.block {
+            renderer.block(block);
+        }
+
+        for child in &self.children {
+            child.draw(renderer);
+        }
+    }
+}
+
+/// `Button` of `embedded_ui::widgets::button::Button<'_, ...>`.
+/// [`core::any::type_name`] format is not guaranteed, so the names may
+/// change with the compiler version.
+fn short_type_name(name: &'static str) -> &'static str {
+    let path = name.split('<').next().unwrap_or(name);
+    path.rsplit("::").next().unwrap_or(path)
+}
+
+/// Indented text tree of widgets, a line per widget with its name, id,
+/// bounds, requested size, state tag and state, e.g.
+/// `Button #ok 0,0 64x16 [fill shrink] <ButtonState> ButtonState { .. }`.
+/// See [`UI::dump_tree`](crate::ui::UI::dump_tree).
+///
+/// Custom ids are printed as is, unique ones as `#_`, as their values
+/// depend on the order elements are created in. Widget and state names are
+/// short type names, which are stable for the same compiler version only,
+/// see [`core::any::type_name`].
+pub struct TreeDump<C: UiColor> {
+    pub(crate) root: Option<WidgetDebug<C>>,
+}
+
+impl<C: UiColor> TreeDump<C> {
+    fn write_node(f: &mut fmt::Formatter<'_>, node: &WidgetDebug<C>, depth: usize) -> fmt::Result {
+        write!(f, "{:indent$}{}", "", node.name, indent = depth * 2)?;
+
+        match node.id {
+            Some(ElId::Unique(_)) => f.write_str(" #_")?,
+            Some(ElId::Custom(id)) => write!(f, " #{id}")?,
+            None => {},
+        }
+
+        let Rectangle { top_left, size } = node.bounds;
+        write!(
+            f,
+            " {},{} {}x{} [{} {}]",
+            top_left.x, top_left.y, size.width, size.height, node.size.width, node.size.height
+        )?;
+
+        if !node.state_tag.is_stateless() {
+            write!(f, " <{}>", short_type_name(node.state_tag.name()))?;
+        }
+
+        if let Some(state) = &node.state {
+            write!(f, " {state}")?;
+        }
+        writeln!(f)?;
+
+        for child in &node.children {
+            Self::write_node(f, child, depth + 1)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<C: UiColor> fmt::Display for TreeDump<C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.root {
+            Some(root) => Self::write_node(f, root, 0),
+            None => Ok(()),
+        }
+    }
+}
+
+/// How an event went through the tree
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EventRoute {
+    /// Captured by the innermost element with id, `None` if only by elements
+    /// without one
+    Captured(Option<ElId>),
+    /// No element captured the event, it bubbled up to the root from the
+    /// element
+    BubbledUp(ElId),
+    Ignored,
+}
+
+/// Event dispatched in the last tick in debug mode, see
+/// [`UI::event_trace`](crate::ui::UI::event_trace)
+#[derive(Clone, Copy, Debug)]
+pub struct EventTrace {
+    /// Common part of the event if it has one
+    pub event: Option<CommonEvent>,
+    pub route: EventRoute,
+}
+
+/// Highlight of the focused element
+pub(crate) fn draw_focus<R: Renderer>(renderer: &mut R, bounds: Rectangle) {
+    outline(renderer, bounds, R::Color::from_rgb(0xff, 0xd7, 0x00), 2);
+}
+
+/// Highlight of an element event went through
+pub(crate) fn draw_route<R: Renderer>(renderer: &mut R, route: EventRoute, bounds: Rectangle) {
+    let color = match route {
+        EventRoute::Captured(_) => R::Color::from_rgb(0x00, 0xff, 0x00),
+        EventRoute::BubbledUp(_) | EventRoute::Ignored => R::Color::from_rgb(0xff, 0x80, 0x00),
+    };
+
+    // Inside of the focus highlight, the same element is often both
+    outline(renderer, bounds.offset(-2), color, 1);
+}
+
+fn outline<R: Renderer>(renderer: &mut R, bounds: Rectangle, color: R::Color, width: u32) {
+    renderer.block(Border::new().color(color).width(width).into_outline(bounds));
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::{string::ToString, vec::Vec};
+
+    use crate::{
+        col,
+        event::CommonEvent,
+        helpers::{button, checkbox, container},
+        testing::{harness, snapshot, Script},
+    };
+
+    use super::EventRoute;
+
+    #[test]
+    fn debug_overlay() {
+        snapshot(
+            "debug",
+            || col![button("OK"), checkbox(|_| ())].padding(2).into(),
+            |h| {
+                let mut h = h.auto_focus().events([
+                    CommonEvent::ToggleDebug.into(),
+                    CommonEvent::FocusMove(1).into(),
+                    CommonEvent::FocusButtonDown.into(),
+                ]);
+                let routes: Vec<_> = h.ui().event_trace().iter().map(|trace| trace.route).collect();
+                assert!(matches!(
+                    routes[..],
+                    [EventRoute::BubbledUp(_), EventRoute::Captured(Some(_))]
+                ));
+                h
+            },
+        );
+    }
+
+    #[test]
+    fn dump_tree() {
+        let mut harness: Script = harness(
+            col!["Title", container("Box"), button("OK").identify("ok"), button("No")].padding(2),
+        );
+
+        let dump = harness.ui().dump_tree().to_string();
+        assert_eq!(
+            dump.lines().collect::<Vec<_>>(),
+            [
+                "Linear 0,0 64x32 [fill fill]",
+                "  Text 2,2 60x6 [shrink shrink] <TextState> TextState { max_width: 60, height: 6, .. }",
+                "  Container 2,8 60x8 [fill fill]",
+                "    Text 3,9 58x6 [shrink shrink] <TextState> TextState { max_width: 58, height: 6, .. }",
+                "  Button #ok 2,16 60x7 [fill fill] <ButtonState> ButtonState { pressed: false, \
+                 consumed: false, outline_width: Some(Animated(0)) }",
+                "    Text 3,17 58x5 [shrink shrink] <TextState> TextState { max_width: 58, height: 6, .. }",
+                "  Button #_ 2,23 60x7 [fill fill] <ButtonState> ButtonState { pressed: false, \
+                 consumed: false, outline_width: Some(Animated(0)) }",
+                "    Text 3,24 58x5 [shrink shrink] <TextState> TextState { max_width: 58, height: 6, .. }",
+            ]
+        );
+    }
 }
@@ -3,6 +3,8 @@ use core::{borrow::Borrow, sync::atomic::AtomicUsize};
 use alloc::boxed::Box;
 
 use crate::{
+    action::{ActionResult, WidgetAction},
+    debug::WidgetDebug,
     event::Event,
     layout::{Layout, Viewport},
     render::Renderer,
@@ -14,6 +16,36 @@ use crate::{
 
 static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
 
+/// Counter of the innermost [`ElId::scope`], unique ids come from it instead
+/// of the global counter while it's set
+mod scope {
+    #[cfg(any(test, feature = "std"))]
+    std::thread_local! {
+        static NEXT: core::cell::Cell<Option<usize>> = const { core::cell::Cell::new(None) };
+    }
+
+    #[cfg(any(test, feature = "std"))]
+    pub(super) fn replace(next: Option<usize>) -> Option<usize> {
+        NEXT.with(|cell| cell.replace(next))
+    }
+
+    /// Without threads a single scope is active at a time
+    #[cfg(not(any(test, feature = "std")))]
+    static NEXT: core::sync::atomic::AtomicUsize = core::sync::atomic::AtomicUsize::new(NONE);
+
+    #[cfg(not(any(test, feature = "std")))]
+    const NONE: usize = usize::MAX;
+
+    #[cfg(not(any(test, feature = "std")))]
+    pub(super) fn replace(next: Option<usize>) -> Option<usize> {
+        use core::sync::atomic::Ordering;
+
+        let prev = NEXT.load(Ordering::Relaxed);
+        NEXT.store(next.unwrap_or(NONE), Ordering::Relaxed);
+        (prev != NONE).then_some(prev)
+    }
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[cfg_attr(feature = "defmt", derive(::defmt::Format))]
 pub enum ElId {
@@ -26,8 +58,28 @@ impl ElId {
         Self::Custom(name)
     }
 
+    /// Id unique in the process, or in the current [`ElId::scope`]
     pub fn unique() -> Self {
-        Self::Unique(NEXT_ID.fetch_add(1, core::sync::atomic::Ordering::Relaxed))
+        let id = match scope::replace(None) {
+            Some(next) => {
+                scope::replace(Some(next + 1));
+                next
+            },
+            None => NEXT_ID.fetch_add(1, core::sync::atomic::Ordering::Relaxed),
+        };
+
+        Self::Unique(id)
+    }
+
+    /// Build elements with unique ids counted from zero, so the same view
+    /// built again gives the same ids to its elements and focus survives
+    /// rebuilds. Ids are unique only among elements built in the scope,
+    /// so the whole tree of a UI must be built in it.
+    pub fn scope<T>(build: impl FnOnce() -> T) -> T {
+        let outer = scope::replace(Some(0));
+        let built = build();
+        scope::replace(outer);
+        built
     }
 }
 
@@ -37,22 +89,36 @@ impl From<&'static str> for ElId {
     }
 }
 
+enum ElWidget<'a, Message, R: Renderer, E: Event, S> {
+    Boxed(Box<dyn Widget<Message, R, E, S> + 'a>),
+    Borrowed(&'a mut dyn Widget<Message, R, E, S>),
+}
+
 #[cfg_attr(feature = "defmt", derive(::defmt::Format))]
 pub struct El<'a, Message, R: Renderer, E: Event, S> {
-    widget: Box<dyn Widget<Message, R, E, S> + 'a>,
+    widget: ElWidget<'a, Message, R, E, S>,
 }
 
 impl<'a, Message, R: Renderer, E: Event, S> Widget<Message, R, E, S> for El<'a, Message, R, E, S> {
     fn id(&self) -> Option<ElId> {
-        self.widget.id()
+        self.widget().id()
     }
 
     fn tree_ids(&self) -> alloc::vec::Vec<ElId> {
-        self.widget.tree_ids()
+        self.widget().tree_ids()
+    }
+
+    fn focus_bounds(
+        &self,
+        state: &mut StateNode,
+        layout: Layout,
+        bounds: &mut alloc::vec::Vec<(ElId, embedded_graphics::primitives::Rectangle)>,
+    ) {
+        self.widget().focus_bounds(state, layout, bounds)
     }
 
     fn size(&self, viewport: &Viewport) -> Size<Length> {
-        self.widget.size(viewport)
+        self.widget().size(viewport)
     }
 
     fn layout(
@@ -63,7 +129,7 @@ impl<'a, Message, R: Renderer, E: Event, S> Widget<Message, R, E, S> for El<'a,
         limits: &crate::layout::Limits,
         viewport: &Viewport,
     ) -> crate::layout::LayoutNode {
-        self.widget.layout(ctx, state_tree, styler, limits, viewport)
+        self.widget().layout(ctx, state_tree, styler, limits, viewport)
     }
 
     fn draw(
@@ -75,7 +141,25 @@ impl<'a, Message, R: Renderer, E: Event, S> Widget<Message, R, E, S> for El<'a,
         layout: Layout,
         viewport: &Viewport,
     ) {
-        self.widget.draw(ctx, state_tree, renderer, styler, layout, viewport)
+        if !ctx.is_dirty(layout.bounds()) {
+            return;
+        }
+
+        self.widget().draw(ctx, state_tree, renderer, styler, layout, viewport)
+    }
+
+    fn draw_overlay(
+        &self,
+        ctx: &mut UiCtx<Message>,
+        state_tree: &mut StateNode,
+        renderer: &mut R,
+        styler: &S,
+        layout: Layout,
+        viewport: &Viewport,
+    ) {
+        // Overlays are outside of element bounds, so dirtiness is checked by
+        // overlay content
+        self.widget().draw_overlay(ctx, state_tree, renderer, styler, layout, viewport)
     }
 
     fn on_event(
@@ -85,29 +169,82 @@ impl<'a, Message, R: Renderer, E: Event, S> Widget<Message, R, E, S> for El<'a,
         state: &mut StateNode,
         layout: Layout,
     ) -> crate::event::EventResponse<E> {
-        self.widget.on_event(ctx, event, state, layout)
+        let response = self.widget_mut().on_event(ctx, event, state, layout);
+
+        if response.is_break() {
+            if let Some(id) = self.id() {
+                ctx.captured_by(id);
+            }
+        }
+
+        response
+    }
+
+    fn overlay_event(
+        &mut self,
+        ctx: &mut UiCtx<Message>,
+        event: E,
+        state: &mut StateNode,
+        layout: Layout,
+    ) -> Option<crate::event::EventResponse<E>> {
+        self.widget_mut().overlay_event(ctx, event, state, layout)
+    }
+
+    fn perform(
+        &mut self,
+        ctx: &mut UiCtx<Message>,
+        action: &WidgetAction,
+        state: &mut StateNode,
+        layout: Layout,
+    ) -> ActionResult {
+        self.widget_mut().perform(ctx, action, state, layout)
     }
 
     fn state_tag(&self) -> crate::state::StateTag {
-        self.widget.state_tag()
+        self.widget().state_tag()
     }
 
     fn state(&self) -> state::State {
-        self.widget.state()
+        self.widget().state()
     }
 
     fn state_children(&self) -> alloc::vec::Vec<StateNode> {
-        self.widget.state_children()
+        self.widget().state_children()
+    }
+
+    fn debug(
+        &self,
+        state: &mut StateNode,
+        layout: Layout,
+        viewport: &Viewport,
+    ) -> Option<WidgetDebug<R::Color>> {
+        self.widget().debug(state, layout, viewport)
     }
 }
 
 impl<'a, Message, R: Renderer, E: Event, S> El<'a, Message, R, E, S> {
     pub fn new(widget: impl Widget<Message, R, E, S> + 'a) -> Self {
-        Self { widget: Box::new(widget) }
+        Self { widget: ElWidget::Boxed(Box::new(widget)) }
+    }
+
+    /// Element referencing a widget owned by someone else, e.g. a `static`.
+    /// Does not allocate, unlike [`El::new`].
+    pub fn borrowed(widget: &'a mut dyn Widget<Message, R, E, S>) -> Self {
+        Self { widget: ElWidget::Borrowed(widget) }
+    }
+
+    pub fn widget(&self) -> &(dyn Widget<Message, R, E, S> + 'a) {
+        match &self.widget {
+            ElWidget::Boxed(widget) => widget.as_ref(),
+            ElWidget::Borrowed(widget) => *widget,
+        }
     }
 
-    pub fn widget(&self) -> &dyn Widget<Message, R, E, S> {
-        self.widget.as_ref()
+    fn widget_mut(&mut self) -> &mut (dyn Widget<Message, R, E, S> + 'a) {
+        match &mut self.widget {
+            ElWidget::Boxed(widget) => widget.as_mut(),
+            ElWidget::Borrowed(widget) => *widget,
+        }
     }
 }
 
@@ -115,7 +252,7 @@ impl<'a, Message, R: Renderer, E: Event, S> Borrow<dyn Widget<Message, R, E, S>
     for El<'a, Message, R, E, S>
 {
     fn borrow(&self) -> &(dyn Widget<Message, R, E, S> + 'a) {
-        self.widget.borrow()
+        self.widget()
     }
 }
 
@@ -123,6 +260,6 @@ impl<'a, Message, R: Renderer, E: Event, S> Borrow<dyn Widget<Message, R, E, S>
     for &El<'a, Message, R, E, S>
 {
     fn borrow(&self) -> &(dyn Widget<Message, R, E, S> + 'a) {
-        self.widget.borrow()
+        self.widget()
     }
 }
@@ -2,8 +2,9 @@ use core::fmt::Debug;
 use core::{marker::PhantomData, ops::ControlFlow};
 
 use alloc::vec::Vec;
+use embedded_graphics::geometry::Point;
 
-use crate::el::ElId;
+use crate::{el::ElId, focus::Direction, gesture::Gesture};
 
 #[derive(Clone, Debug)]
 pub enum Capture {
@@ -40,10 +41,12 @@ impl<E: Event> Into<EventResponse<E>> for Propagate<E> {
 
 pub type EventResponse<E> = ControlFlow<Capture, Propagate<E>>;
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum CommonEvent {
     /// Moves focus by current ±offset
     FocusMove(i32),
+    /// Moves focus to the nearest element in direction, e.g. by d-pad
+    FocusDirection(Direction),
     /// Moves focus starting from back (internal usage only)
     // FocusMoveRev(i32),
     /// Focus button (e.g. enter key) is down
@@ -53,6 +56,22 @@ pub enum CommonEvent {
     /// Quit the UI. Can be captured by for example some dialog like
     /// "Are you sure you wan't to quit?"
     Exit,
+    /// Go back, e.g. to the previous page of
+    /// [`Router`](crate::widgets::router::Router)
+    Back,
+    /// Pointer, e.g. touch panel or mouse, is pressed at the point. The
+    /// element under it gets focused.
+    PointerDown(Point),
+    /// Pointer moved, being pressed or not
+    PointerMove(Point),
+    /// Pointer is released
+    PointerUp(Point),
+    /// Focus button gesture, recognized by UI from button events if it has
+    /// a clock
+    Gesture(Gesture),
+    /// Toggle debug mode, see [`UI::debug`](crate::ui::UI::debug). UI
+    /// handles it itself, widgets don't receive it.
+    ToggleDebug,
 }
 
 // Unused
@@ -70,46 +89,66 @@ pub trait Event: Clone + From<CommonEvent> + Debug {
 
     fn as_common(&self) -> Option<CommonEvent>;
 
-    // TODO: This might better be split and moved to separate traits such as
-    // `AsSelectShift`, etc. so if user don't want to use Slider for example, these
-    // methods don't need to be implemented.  Or the easier way is to make these
-    // methods return `None` or use `FocusMove` by default.
-    fn as_select_shift(&self) -> Option<i32>;
-    fn as_slider_shift(&self) -> Option<i32>;
-    fn as_knob_rotation(&self) -> Option<i32>;
-    fn as_input_letter_scroll(&self) -> Option<i32>;
-    fn as_scroll_offset(&self) -> Option<i32>;
+    /// Offset of [`CommonEvent::FocusMove`], used as a default value shift by
+    /// capability traits of widgets such as [`SliderEvent`], so the same
+    /// control that moves focus changes the value of an active widget.
+    fn as_focus_move(&self) -> Option<i32> {
+        match self.as_common() {
+            Some(CommonEvent::FocusMove(offset)) => Some(offset),
+            _ => None,
+        }
+    }
 }
 
-#[derive(Clone, Debug)]
-pub struct EventStub;
-
-impl Event for EventStub {
-    fn as_common(&self) -> Option<CommonEvent> {
-        None
+/// Events [`Slider`](crate::widgets::slider::Slider) can be controlled by.
+/// An empty impl is enough for event types moving the slider by the same
+/// input that moves focus.
+pub trait SliderEvent: Event {
+    /// Shift of the slider value by steps, focus move by default
+    fn as_slider_shift(&self) -> Option<i32> {
+        self.as_focus_move()
     }
+}
 
+/// Events [`Select`](crate::widgets::select::Select) can be controlled by
+pub trait SelectEvent: Event {
+    /// Shift of the chosen select option, focus move by default
     fn as_select_shift(&self) -> Option<i32> {
-        None
+        self.as_focus_move()
     }
+}
 
-    fn as_slider_shift(&self) -> Option<i32> {
-        None
+/// Events [`Scrollable`](crate::widgets::scrollable::Scrollable) can be
+/// controlled by
+pub trait ScrollEvent: Event {
+    /// Offset of scrollable content in pixels, focus move by default
+    fn as_scroll_offset(&self) -> Option<i32> {
+        self.as_focus_move()
     }
+}
 
+/// Events [`Knob`](crate::widgets::knob::Knob) can be controlled by
+pub trait KnobEvent: Event {
+    /// Rotation of the knob by steps, focus move by default
     fn as_knob_rotation(&self) -> Option<i32> {
-        None
+        self.as_focus_move()
     }
+}
 
-    fn as_input_letter_scroll(&self) -> Option<i32> {
-        None
-    }
+#[derive(Clone, Debug)]
+pub struct EventStub;
 
-    fn as_scroll_offset(&self) -> Option<i32> {
+impl Event for EventStub {
+    fn as_common(&self) -> Option<CommonEvent> {
         None
     }
 }
 
+impl SelectEvent for EventStub {}
+impl SliderEvent for EventStub {}
+impl KnobEvent for EventStub {}
+impl ScrollEvent for EventStub {}
+
 impl From<CommonEvent> for EventStub {
     fn from(_: CommonEvent) -> Self {
         Self
@@ -132,6 +171,15 @@ where
     }
 }
 
+/// Controls combined together, e.g. encoder and its button
+impl<E: Event, A: Controls<E>, B: Controls<E>> Controls<E> for (A, B) {
+    fn events(&mut self) -> Vec<E> {
+        let mut events = self.0.events();
+        events.extend(self.1.events());
+        events
+    }
+}
+
 pub struct NullControls<E: Event> {
     marker: PhantomData<E>,
 }
@@ -147,3 +195,85 @@ impl<E: Event> Default for NullControls<E> {
         Self { marker: PhantomData }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+    use embedded_graphics::{geometry::Size, pixelcolor::Rgb888};
+
+    use super::{CommonEvent, Event, KnobEvent, ScrollEvent, SelectEvent, SliderEvent};
+    use crate::{
+        el::El,
+        helpers::{knob, slider_h},
+        render::DrawTargetRenderer,
+        testing::{self, Harness},
+        theme::Theme,
+    };
+
+    /// Dial turning values of active widgets, so focus moves don't change
+    /// them
+    #[derive(Clone, Copy, Debug)]
+    enum Dial {
+        Common(CommonEvent),
+        Turn(i32),
+    }
+
+    impl From<CommonEvent> for Dial {
+        fn from(value: CommonEvent) -> Self {
+            Self::Common(value)
+        }
+    }
+
+    impl Event for Dial {
+        fn as_common(&self) -> Option<CommonEvent> {
+            match *self {
+                Self::Common(common) => Some(common),
+                Self::Turn(_) => None,
+            }
+        }
+    }
+
+    impl SliderEvent for Dial {
+        fn as_slider_shift(&self) -> Option<i32> {
+            match *self {
+                Self::Turn(steps) => Some(steps),
+                Self::Common(_) => None,
+            }
+        }
+    }
+
+    impl KnobEvent for Dial {}
+    impl SelectEvent for Dial {}
+    impl ScrollEvent for Dial {}
+
+    fn activated<E: Event>(
+        root: El<'static, u8, DrawTargetRenderer<Rgb888>, E, Theme<Rgb888>>,
+    ) -> Harness<'static, u8, Rgb888, E, Theme<Rgb888>> {
+        let mut harness = Harness::new(root, Size::new(64, 32))
+            .auto_focus()
+            .events([CommonEvent::FocusButtonDown.into(), CommonEvent::FocusButtonUp.into()]);
+        harness.messages();
+        harness
+    }
+
+    #[test]
+    fn focus_move_shifts_value_by_default() {
+        let mut harness = activated::<testing::Event>(slider_h(|value| value).into())
+            .events([CommonEvent::FocusMove(1).into()]);
+        assert!(!harness.messages().is_empty());
+
+        let mut harness = activated::<Dial>(knob(|value| value).into())
+            .events([CommonEvent::FocusMove(1).into()]);
+        assert!(!harness.messages().is_empty());
+    }
+
+    #[test]
+    fn widget_shifts_by_capability_of_event() {
+        let mut harness = activated::<Dial>(slider_h(|value| value).into())
+            .events([CommonEvent::FocusMove(1).into()]);
+        assert_eq!(harness.messages(), Vec::<u8>::new());
+
+        let mut harness = harness.events([Dial::Turn(1)]);
+        assert!(!harness.messages().is_empty());
+    }
+}
@@ -1,32 +1,274 @@
-// use crate::{
-//     el::{El, ElId},
-//     event::{Event, EventResponse},
-//     render::Renderer,
-//     ui::UiCtx,
-// };
-
-// #[derive(Clone, Copy)]
-// enum FocusResult {
-//     Child(ElId),
-//     Outside(i32),
-// }
-
-// pub fn for_container<'a, Message, R: Renderer, E: Event, S>(
-//     ctx: &mut UiCtx<Message>,
-//     children: &[El<'a, Message, R, E, S>],
-// ) -> EventResponse<E> {
-//     let new_focus_index = child_index as i32 + focus_offset;
-
-//     if new_focus_index < 0 {
-//         return FocusResult::Outside(new_focus_index);
-//     }
-
-//     let new_focused_child =
-//         children.iter().filter_map(|child| child.id()).nth(new_focus_index as usize);
-
-//     if let Some(new_focused_child) = new_focused_child {
-//         FocusResult::Child(new_focused_child)
-//     } else {
-//         FocusResult::Outside(new_focus_index)
-//     }
-// }
+use alloc::vec::Vec;
+use embedded_graphics::{geometry::Point, primitives::Rectangle};
+
+use crate::{
+    axis::{Axial, Axis},
+    el::ElId,
+    event::{Capture, CommonEvent, Event, EventResponse, Propagate},
+    state::StateNode,
+    ui::UiCtx,
+};
+
+/// Direction of spatial focus move, e.g. by d-pad or joystick
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(::defmt::Format))]
+pub enum Direction {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+impl Direction {
+    pub fn axis(self) -> Axis {
+        match self {
+            Direction::Up | Direction::Down => Axis::Y,
+            Direction::Left | Direction::Right => Axis::X,
+        }
+    }
+
+    fn is_forward(self) -> bool {
+        matches!(self, Direction::Down | Direction::Right)
+    }
+}
+
+/// Start and end of rectangle on the axis
+fn span(rect: Rectangle, axis: Axis) -> (i32, i32) {
+    let start = rect.top_left.main_for(axis);
+    (start, start + rect.size.main_for(axis) as i32)
+}
+
+/// The nearest element in `direction` from `from` bounds. Elements lying in
+/// the same row or column are preferred, e.g. moving right from a button
+/// picks the next one in its row, not a closer one from the row below.
+pub fn nearest(
+    from: Rectangle,
+    direction: Direction,
+    candidates: impl IntoIterator<Item = (ElId, Rectangle)>,
+) -> Option<ElId> {
+    let axis = direction.axis();
+    let (from_start, from_end) = span(from, axis);
+    let (from_cross_start, from_cross_end) = span(from, axis.invert());
+    let from_center = from_start + from_end;
+    let from_cross_center = from_cross_start + from_cross_end;
+
+    candidates
+        .into_iter()
+        .filter_map(|(id, bounds)| {
+            let (start, end) = span(bounds, axis);
+            let (cross_start, cross_end) = span(bounds, axis.invert());
+
+            // Centers are doubled not to lose precision
+            let center = start + end;
+            let distance = if direction.is_forward() {
+                (center > from_center).then(|| start - from_end)?
+            } else {
+                (center < from_center).then(|| from_start - end)?
+            }
+            .max(0) as u32;
+
+            let in_line = cross_start < from_cross_end && from_cross_start < cross_end;
+            let cross_distance = (cross_start + cross_end).abs_diff(from_cross_center) / 2;
+            let score = if in_line { distance } else { distance + cross_distance * 2 };
+
+            Some(((!in_line, score, cross_distance), id))
+        })
+        .min_by_key(|&(key, _)| key)
+        .map(|(_, id)| id)
+}
+
+/// Element under the pointer. Nested elements follow their parents in
+/// `candidates`, so the last one containing `point` is the innermost.
+pub fn hit(point: Point, candidates: impl IntoIterator<Item = (ElId, Rectangle)>) -> Option<ElId> {
+    candidates.into_iter().filter(|(_, bounds)| bounds.contains(point)).last().map(|(id, _)| id)
+}
+
+/// What happens when focus reaches an end of a container being a focus
+/// scope. Focus moves inside the scope first, e.g. directional move picks
+/// the nearest element of the scope even if there is a closer one outside.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(::defmt::Format))]
+pub enum FocusScope {
+    /// Hand focus to the parent, which moves it outside of the scope
+    #[default]
+    Pass,
+    /// Go to the other end of the scope. Directional moves stop at the edge.
+    Wrap,
+    /// Keep focus inside of the scope, e.g. for modal dialogs
+    Trap,
+}
+
+/// Focus order of scope elements, cached on layout
+#[derive(Debug, Default)]
+pub(crate) struct ScopeState {
+    pub order: Vec<ElId>,
+}
+
+impl FocusScope {
+    /// Move focus bubbled up from `origin` inside of the scope with focus
+    /// `order`. `bounds` gives bounds of scope elements for directional
+    /// moves. Event bubbles further if focus leaves the scope.
+    pub fn move_focus<Message, E: Event>(
+        self,
+        ctx: &mut UiCtx<Message>,
+        order: &[ElId],
+        bounds: impl FnOnce() -> Vec<(ElId, Rectangle)>,
+        origin: ElId,
+        event: E,
+    ) -> EventResponse<E> {
+        let next = match event.as_common() {
+            Some(CommonEvent::FocusMove(offset)) => {
+                let Some(current) = order.iter().position(|&id| id == origin) else {
+                    return Propagate::BubbleUp(origin, event).into();
+                };
+
+                let next = current as i32 + offset;
+                let last = order.len() as i32 - 1;
+
+                let next = match self {
+                    _ if (0..=last).contains(&next) => next,
+                    FocusScope::Pass => return Propagate::BubbleUp(origin, event).into(),
+                    FocusScope::Wrap => next.rem_euclid(last + 1),
+                    FocusScope::Trap => next.clamp(0, last),
+                };
+
+                Some(order[next as usize])
+            },
+            Some(CommonEvent::FocusDirection(direction)) => {
+                let bounds = bounds();
+
+                let Some(&(_, from)) = bounds.iter().find(|&&(id, _)| id == origin) else {
+                    return Propagate::BubbleUp(origin, event).into();
+                };
+
+                match nearest(from, direction, bounds.into_iter().filter(|&(id, _)| id != origin)) {
+                    Some(next) => Some(next),
+                    None if self == FocusScope::Pass => {
+                        return Propagate::BubbleUp(origin, event).into()
+                    },
+                    None => None,
+                }
+            },
+            _ => return Propagate::BubbleUp(origin, event).into(),
+        };
+
+        if let Some(next) = next {
+            ctx.focus(next);
+        }
+
+        Capture::Captured.into()
+    }
+
+    /// [`FocusScope::move_focus`] for containers keeping focus order in
+    /// [`ScopeState`]
+    pub(crate) fn move_focus_cached<Message, E: Event>(
+        self,
+        ctx: &mut UiCtx<Message>,
+        state: &mut StateNode,
+        bounds: impl FnOnce(&mut StateNode) -> Vec<(ElId, Rectangle)>,
+        origin: ElId,
+        event: E,
+    ) -> EventResponse<E> {
+        let order = core::mem::take(&mut state.get_mut::<ScopeState>().order);
+        let response = self.move_focus(ctx, &order, || bounds(state), origin, event);
+        state.get_mut::<ScopeState>().order = order;
+
+        response
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use embedded_graphics::{
+        geometry::{Point, Size},
+        primitives::Rectangle,
+    };
+
+    use crate::{
+        el::ElId,
+        event::{CommonEvent, Propagate},
+        testing::Event,
+        ui::UiCtx,
+    };
+
+    use super::{hit, nearest, Direction, FocusScope};
+
+    /// 3x2 pad of 10x10 buttons with 2px gaps, named by row and column
+    fn pad() -> [(ElId, Rectangle); 6] {
+        let names = ["00", "01", "02", "10", "11", "12"];
+
+        core::array::from_fn(|index| {
+            let (row, col) = (index as i32 / 3, index as i32 % 3);
+            (
+                ElId::new(names[index]),
+                Rectangle::new(Point::new(col * 12, row * 12), Size::new(10, 10)),
+            )
+        })
+    }
+
+    fn go(from: usize, direction: Direction) -> Option<ElId> {
+        let pad = pad();
+        nearest(pad[from].1, direction, pad.into_iter().filter(|&(id, _)| id != pad[from].0))
+    }
+
+    #[test]
+    fn moves_in_grid() {
+        assert_eq!(go(0, Direction::Right), Some(ElId::new("01")));
+        assert_eq!(go(0, Direction::Down), Some(ElId::new("10")));
+        assert_eq!(go(4, Direction::Up), Some(ElId::new("01")));
+        assert_eq!(go(4, Direction::Left), Some(ElId::new("10")));
+    }
+
+    #[test]
+    fn stops_at_edge() {
+        assert_eq!(go(2, Direction::Right), None);
+        assert_eq!(go(1, Direction::Up), None);
+    }
+
+    #[test]
+    fn prefers_same_row() {
+        let from = Rectangle::new(Point::new(0, 0), Size::new(10, 10));
+        let candidates = [
+            (ElId::new("below"), Rectangle::new(Point::new(12, 12), Size::new(10, 10))),
+            (ElId::new("far"), Rectangle::new(Point::new(40, 0), Size::new(10, 10))),
+        ];
+
+        assert_eq!(nearest(from, Direction::Right, candidates), Some(ElId::new("far")));
+    }
+
+    #[test]
+    fn hits_innermost() {
+        let mut candidates =
+            vec![(ElId::new("pad"), Rectangle::new(Point::zero(), Size::new(34, 22)))];
+        candidates.extend(pad());
+
+        assert_eq!(hit(Point::new(13, 13), candidates.clone()), Some(ElId::new("11")));
+        assert_eq!(hit(Point::new(11, 5), candidates.clone()), Some(ElId::new("pad")));
+        assert_eq!(hit(Point::new(40, 5), candidates), None);
+    }
+
+    /// Move focus by `offset` from the last of three elements
+    fn move_from_last(scope: FocusScope, offset: i32) -> (Option<ElId>, bool) {
+        let order = [ElId::new("a"), ElId::new("b"), ElId::new("c")];
+        let mut ctx = UiCtx::<()>::new();
+
+        let response = scope.move_focus(
+            &mut ctx,
+            &order,
+            Default::default,
+            order[2],
+            Event(CommonEvent::FocusMove(offset)),
+        );
+        let bubbled = matches!(response, core::ops::ControlFlow::Continue(Propagate::BubbleUp(..)));
+
+        (ctx.focused(), bubbled)
+    }
+
+    #[test]
+    fn scope_ends() {
+        assert_eq!(move_from_last(FocusScope::Pass, -1), (Some(ElId::new("b")), false));
+        assert_eq!(move_from_last(FocusScope::Pass, 1), (None, true));
+        assert_eq!(move_from_last(FocusScope::Wrap, 1), (Some(ElId::new("a")), false));
+        assert_eq!(move_from_last(FocusScope::Trap, 1), (Some(ElId::new("c")), false));
+    }
+}
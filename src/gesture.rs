@@ -0,0 +1,214 @@
+use core::time::Duration;
+
+use crate::{event::CommonEvent, time::Instant};
+
+/// Gesture of the focus button recognized from its down and up events
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(::defmt::Format))]
+pub enum Gesture {
+    /// Button is held for long enough. Comes while it's still down, the
+    /// following release is not a click.
+    LongPress,
+    /// Button is clicked the second time shortly after the first click.
+    /// Comes after the second release, so both clicks are seen first.
+    DoublePress,
+    /// Button is still held, comes periodically after a delay, e.g. to
+    /// increment value until released
+    Repeat,
+}
+
+/// Recognizes [`Gesture`]s from [`CommonEvent`]s and the UI clock. Moving
+/// focus cancels the gesture in progress.
+#[derive(Clone, Copy)]
+pub struct Gestures {
+    long_press: Duration,
+    double_press: Duration,
+    repeat_delay: Duration,
+    repeat_interval: Duration,
+    /// When the button went down, while it's held
+    down: Option<Instant>,
+    /// When the last click ended, while the second one can make it double
+    last_click: Option<Instant>,
+    double: bool,
+    long_pressed: bool,
+    next_repeat: Option<Instant>,
+}
+
+impl Default for Gestures {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Gestures {
+    pub const fn new() -> Self {
+        Self {
+            long_press: Duration::from_millis(500),
+            double_press: Duration::from_millis(300),
+            repeat_delay: Duration::from_millis(500),
+            repeat_interval: Duration::from_millis(100),
+            down: None,
+            last_click: None,
+            double: false,
+            long_pressed: false,
+            next_repeat: None,
+        }
+    }
+
+    /// How long the button must be held for [`Gesture::LongPress`]
+    pub fn long_press(mut self, hold: Duration) -> Self {
+        self.long_press = hold;
+        self
+    }
+
+    /// Max time between the first click and the second press for
+    /// [`Gesture::DoublePress`]
+    pub fn double_press(mut self, window: Duration) -> Self {
+        self.double_press = window;
+        self
+    }
+
+    /// [`Gesture::Repeat`] comes `delay` after press and then every
+    /// `interval`
+    pub fn repeat(mut self, delay: Duration, interval: Duration) -> Self {
+        self.repeat_delay = delay;
+        self.repeat_interval = interval;
+        self
+    }
+
+    fn cancel(&mut self) {
+        self.down = None;
+        self.last_click = None;
+        self.next_repeat = None;
+    }
+
+    /// Feed an event, gesture it completes is returned
+    pub fn input(&mut self, event: CommonEvent, now: Instant) -> Option<Gesture> {
+        match event {
+            CommonEvent::FocusButtonDown => {
+                self.double = self
+                    .last_click
+                    .is_some_and(|click| now.duration_since(click) <= self.double_press);
+                self.down = Some(now);
+                self.long_pressed = false;
+                self.next_repeat = Some(now + self.repeat_delay);
+                None
+            },
+            CommonEvent::FocusButtonUp => {
+                let down = self.down.take();
+                self.next_repeat = None;
+
+                if down.is_none() || self.long_pressed {
+                    self.last_click = None;
+                    return None;
+                }
+
+                if core::mem::take(&mut self.double) {
+                    self.last_click = None;
+                    Some(Gesture::DoublePress)
+                } else {
+                    self.last_click = Some(now);
+                    None
+                }
+            },
+            CommonEvent::FocusMove(_) | CommonEvent::FocusDirection(_) | CommonEvent::Back => {
+                self.cancel();
+                None
+            },
+            _ => None,
+        }
+    }
+
+    /// Gesture the passed time completes, call until `None` as both
+    /// [`Gesture::LongPress`] and [`Gesture::Repeat`] can be due
+    pub fn poll(&mut self, now: Instant) -> Option<Gesture> {
+        let down = self.down?;
+
+        if !self.long_pressed && now.duration_since(down) >= self.long_press {
+            self.long_pressed = true;
+            return Some(Gesture::LongPress);
+        }
+
+        match self.next_repeat {
+            // Missed repeats are not caught up
+            Some(next) if next <= now => {
+                self.next_repeat = Some(now + self.repeat_interval);
+                Some(Gesture::Repeat)
+            },
+            _ => None,
+        }
+    }
+
+    /// The earliest time some gesture can be recognized at without events
+    pub fn next_deadline(&self) -> Option<Instant> {
+        let down = self.down?;
+        let long_press = (!self.long_pressed).then(|| down + self.long_press);
+
+        match (long_press, self.next_repeat) {
+            (Some(long_press), Some(repeat)) => Some(long_press.min(repeat)),
+            (deadline, None) | (None, deadline) => deadline,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+    use core::time::Duration;
+
+    use crate::{event::CommonEvent, time::Instant};
+
+    use super::{Gesture, Gestures};
+
+    fn at(millis: u64) -> Instant {
+        Instant::from_millis(millis)
+    }
+
+    fn poll_all(gestures: &mut Gestures, now: Instant) -> Vec<Gesture> {
+        core::iter::from_fn(|| gestures.poll(now)).collect()
+    }
+
+    #[test]
+    fn double_press() {
+        let mut gestures = Gestures::new();
+
+        assert_eq!(gestures.input(CommonEvent::FocusButtonDown, at(0)), None);
+        assert_eq!(gestures.input(CommonEvent::FocusButtonUp, at(50)), None);
+        assert_eq!(gestures.input(CommonEvent::FocusButtonDown, at(200)), None);
+        assert_eq!(gestures.input(CommonEvent::FocusButtonUp, at(250)), Some(Gesture::DoublePress));
+
+        // Too late for the second click
+        gestures.input(CommonEvent::FocusButtonDown, at(1000));
+        gestures.input(CommonEvent::FocusButtonUp, at(1050));
+        gestures.input(CommonEvent::FocusButtonDown, at(1400));
+        assert_eq!(gestures.input(CommonEvent::FocusButtonUp, at(1450)), None);
+    }
+
+    #[test]
+    fn long_press_and_repeat() {
+        let mut gestures =
+            Gestures::new().repeat(Duration::from_millis(200), Duration::from_millis(100));
+
+        gestures.input(CommonEvent::FocusButtonDown, at(0));
+        assert_eq!(gestures.next_deadline(), Some(at(200)));
+        assert!(poll_all(&mut gestures, at(100)).is_empty());
+        assert_eq!(poll_all(&mut gestures, at(200)), [Gesture::Repeat]);
+        assert_eq!(poll_all(&mut gestures, at(500)), [Gesture::LongPress, Gesture::Repeat]);
+        assert_eq!(gestures.next_deadline(), Some(at(600)));
+
+        // Release after long press is neither a click nor a half of double
+        assert_eq!(gestures.input(CommonEvent::FocusButtonUp, at(550)), None);
+        gestures.input(CommonEvent::FocusButtonDown, at(600));
+        assert_eq!(gestures.input(CommonEvent::FocusButtonUp, at(650)), None);
+        assert_eq!(gestures.next_deadline(), None);
+    }
+
+    #[test]
+    fn focus_move_cancels() {
+        let mut gestures = Gestures::new();
+
+        gestures.input(CommonEvent::FocusButtonDown, at(0));
+        gestures.input(CommonEvent::FocusMove(1), at(100));
+        assert!(poll_all(&mut gestures, at(1000)).is_empty());
+    }
+}
@@ -0,0 +1,351 @@
+use alloc::vec::Vec;
+use core::marker::PhantomData;
+
+use embedded_hal::digital::{InputPin, OutputPin};
+
+use crate::{
+    event::{CommonEvent, Controls, Event},
+    focus::Direction,
+};
+
+/// Events a key produces on press and release
+#[derive(Clone, Copy, Debug)]
+pub struct Key {
+    press: Option<CommonEvent>,
+    release: Option<CommonEvent>,
+}
+
+impl Key {
+    /// Key doing nothing, e.g. for unused matrix positions
+    pub const NONE: Self = Self::new(None, None);
+    /// Focus button, such as enter key or encoder button
+    pub const FOCUS: Self =
+        Self::new(Some(CommonEvent::FocusButtonDown), Some(CommonEvent::FocusButtonUp));
+    pub const BACK: Self = Self::new(Some(CommonEvent::Back), None);
+
+    pub const fn new(press: Option<CommonEvent>, release: Option<CommonEvent>) -> Self {
+        Self { press, release }
+    }
+
+    /// D-pad key moving focus in `direction` on press
+    pub const fn direction(direction: Direction) -> Self {
+        Self::new(Some(CommonEvent::FocusDirection(direction)), None)
+    }
+
+    /// Key moving focus by `offset` on press, e.g. next and previous keys
+    pub const fn focus_move(offset: i32) -> Self {
+        Self::new(Some(CommonEvent::FocusMove(offset)), None)
+    }
+
+    fn event<E: Event>(self, pressed: bool) -> Option<E> {
+        if pressed { self.press } else { self.release }.map(E::from)
+    }
+}
+
+/// Integrating debouncer, the state changes only after the same level is
+/// read `samples` times in a row
+#[derive(Clone, Copy, Default)]
+struct Debounce {
+    pressed: bool,
+    count: u8,
+}
+
+impl Debounce {
+    /// New state if it has changed
+    fn update(&mut self, pressed: bool, samples: u8) -> Option<bool> {
+        if pressed == self.pressed {
+            self.count = 0;
+            return None;
+        }
+
+        self.count += 1;
+        if self.count < samples {
+            return None;
+        }
+
+        self.count = 0;
+        self.pressed = pressed;
+        Some(pressed)
+    }
+}
+
+/// Read pin being active at `active_high` level. Failed reads are skipped,
+/// as if the level didn't change.
+fn read<P: InputPin>(pin: &mut P, active_high: bool) -> Option<bool> {
+    pin.is_high().ok().map(|high| high == active_high)
+}
+
+/// Debounced button on an input pin. It's active low by default, as if
+/// pulled up and shorted to the ground when pressed.
+pub struct Button<P: InputPin, E: Event> {
+    pin: P,
+    key: Key,
+    active_high: bool,
+    samples: u8,
+    debounce: Debounce,
+    marker: PhantomData<E>,
+}
+
+impl<P: InputPin, E: Event> Button<P, E> {
+    /// Focus button
+    pub fn new(pin: P) -> Self {
+        Self {
+            pin,
+            key: Key::FOCUS,
+            active_high: false,
+            samples: 3,
+            debounce: Debounce::default(),
+            marker: PhantomData,
+        }
+    }
+
+    pub fn key(mut self, key: Key) -> Self {
+        self.key = key;
+        self
+    }
+
+    pub fn active_high(mut self) -> Self {
+        self.active_high = true;
+        self
+    }
+
+    /// How many polls in a row the pin must keep the level to change the
+    /// button state. Polls must be frequent enough for contact bounce to
+    /// settle within them, 3 by default.
+    pub fn debounce(mut self, samples: u8) -> Self {
+        self.samples = samples.max(1);
+        self
+    }
+
+    pub fn free(self) -> P {
+        self.pin
+    }
+}
+
+impl<P: InputPin, E: Event> Controls<E> for Button<P, E> {
+    fn events(&mut self) -> Vec<E> {
+        read(&mut self.pin, self.active_high)
+            .and_then(|pressed| self.debounce.update(pressed, self.samples))
+            .and_then(|pressed| self.key.event(pressed))
+            .into_iter()
+            .collect()
+    }
+}
+
+/// Quadrature transitions by previous and current `AB` states, invalid ones
+/// (both channels changed) are ignored
+const QUADRATURE: [i8; 16] = [0, -1, 1, 0, 1, 0, 0, -1, -1, 0, 0, 1, 0, 1, -1, 0];
+
+/// Rotary quadrature encoder on two input pins, rotation moves focus by
+/// detents and changes values of active widgets. Its button is a separate
+/// [`Button`], combine them as a tuple of controls.
+///
+/// Encoder must be polled faster than it's rotated, otherwise transitions
+/// are missed.
+pub struct Encoder<A: InputPin, B: InputPin, E: Event> {
+    a: A,
+    b: B,
+    steps_per_detent: i8,
+    reversed: bool,
+    state: Option<u8>,
+    steps: i8,
+    marker: PhantomData<E>,
+}
+
+impl<A: InputPin, B: InputPin, E: Event> Encoder<A, B, E> {
+    pub fn new(a: A, b: B) -> Self {
+        Self {
+            a,
+            b,
+            steps_per_detent: 4,
+            reversed: false,
+            state: None,
+            steps: 0,
+            marker: PhantomData,
+        }
+    }
+
+    /// Quadrature steps per detent, 4 for most encoders
+    pub fn steps_per_detent(mut self, steps: u8) -> Self {
+        self.steps_per_detent = steps.clamp(1, 4) as i8;
+        self
+    }
+
+    /// Swap rotation direction instead of swapping the pins
+    pub fn reversed(mut self) -> Self {
+        self.reversed = true;
+        self
+    }
+
+    pub fn free(self) -> (A, B) {
+        (self.a, self.b)
+    }
+}
+
+impl<A: InputPin, B: InputPin, E: Event> Controls<E> for Encoder<A, B, E> {
+    fn events(&mut self) -> Vec<E> {
+        let (Ok(a), Ok(b)) = (self.a.is_high(), self.b.is_high()) else {
+            return vec![];
+        };
+        let state = (a as u8) << 1 | b as u8;
+
+        // The first read only sets the initial state
+        let Some(prev) = self.state.replace(state) else {
+            return vec![];
+        };
+
+        self.steps += QUADRATURE[(prev << 2 | state) as usize];
+
+        let detents = self.steps / self.steps_per_detent;
+        if detents == 0 {
+            return vec![];
+        }
+        self.steps %= self.steps_per_detent;
+
+        let offset = if self.reversed { -detents } else { detents };
+        vec![CommonEvent::FocusMove(offset as i32).into()]
+    }
+}
+
+/// Keys matrix scanned by driving a row pin low and reading column pins
+/// pulled up, each key is debounced separately
+pub struct Matrix<R: OutputPin, C: InputPin, E: Event, const ROWS: usize, const COLS: usize> {
+    rows: [R; ROWS],
+    cols: [C; COLS],
+    keys: [[Key; COLS]; ROWS],
+    samples: u8,
+    debounce: [[Debounce; COLS]; ROWS],
+    marker: PhantomData<E>,
+}
+
+impl<R: OutputPin, C: InputPin, E: Event, const ROWS: usize, const COLS: usize>
+    Matrix<R, C, E, ROWS, COLS>
+{
+    /// Row pins must be initially high
+    pub fn new(rows: [R; ROWS], cols: [C; COLS], keys: [[Key; COLS]; ROWS]) -> Self {
+        Self {
+            rows,
+            cols,
+            keys,
+            samples: 3,
+            debounce: [[Debounce::default(); COLS]; ROWS],
+            marker: PhantomData,
+        }
+    }
+
+    /// See [`Button::debounce`]
+    pub fn debounce(mut self, samples: u8) -> Self {
+        self.samples = samples.max(1);
+        self
+    }
+
+    pub fn free(self) -> ([R; ROWS], [C; COLS]) {
+        (self.rows, self.cols)
+    }
+}
+
+impl<R: OutputPin, C: InputPin, E: Event, const ROWS: usize, const COLS: usize> Controls<E>
+    for Matrix<R, C, E, ROWS, COLS>
+{
+    fn events(&mut self) -> Vec<E> {
+        let mut events = vec![];
+
+        for (row, row_pin) in self.rows.iter_mut().enumerate() {
+            if row_pin.set_low().is_err() {
+                continue;
+            }
+
+            for (col, col_pin) in self.cols.iter_mut().enumerate() {
+                let event = read(col_pin, false)
+                    .and_then(|pressed| self.debounce[row][col].update(pressed, self.samples))
+                    .and_then(|pressed| self.keys[row][col].event(pressed));
+
+                events.extend(event);
+            }
+
+            // Row stays low on failure and reads as pressed next time,
+            // nothing to do about it here
+            row_pin.set_high().ok();
+        }
+
+        events
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use embedded_hal_mock::eh1::digital::{Mock, State, Transaction};
+
+    use crate::{
+        event::{CommonEvent, Controls},
+        testing::Event,
+    };
+
+    use super::{Button, Encoder, Key, Matrix};
+
+    fn reads(levels: &[State]) -> Mock {
+        Mock::new(&levels.iter().map(|&level| Transaction::get(level)).collect::<Vec<_>>())
+    }
+
+    fn poll(controls: &mut impl Controls<Event>, times: usize) -> Vec<CommonEvent> {
+        (0..times).flat_map(|_| controls.events()).map(|event| event.0).collect()
+    }
+
+    #[test]
+    fn button_debounces() {
+        use State::{High, Low};
+
+        let mut button = Button::new(reads(&[Low, High, Low, Low, Low, High, High, High]));
+
+        let events = poll(&mut button, 8);
+        assert!(matches!(events[..], [CommonEvent::FocusButtonDown, CommonEvent::FocusButtonUp]));
+
+        button.free().done();
+    }
+
+    #[test]
+    fn encoder_counts_detents() {
+        use State::{High, Low};
+
+        // Full cycle clockwise, then half of it back and forth
+        let a = [High, Low, Low, High, High, High, Low, High, High];
+        let b = [High, High, Low, Low, High, Low, Low, Low, High];
+        let mut encoder = Encoder::new(reads(&a), reads(&b));
+
+        let events = poll(&mut encoder, 9);
+        assert!(matches!(events[..], [CommonEvent::FocusMove(1)]));
+
+        let (mut a, mut b) = encoder.free();
+        a.done();
+        b.done();
+    }
+
+    #[test]
+    fn matrix_scans_rows() {
+        use State::{High, Low};
+
+        // Key at the second row and the first column is held, bounce free
+        let row = || {
+            Mock::new(&[
+                Transaction::set(Low),
+                Transaction::set(High),
+                Transaction::set(Low),
+                Transaction::set(High),
+            ])
+        };
+        let mut matrix = Matrix::new(
+            [row(), row()],
+            [reads(&[High, Low, High, Low]), reads(&[High; 4])],
+            [[Key::NONE, Key::NONE], [Key::BACK, Key::NONE]],
+        )
+        .debounce(2);
+
+        let events = poll(&mut matrix, 2);
+        assert!(matches!(events[..], [CommonEvent::Back]));
+
+        let (rows, cols) = matrix.free();
+        rows.into_iter().chain(cols).for_each(|mut pin| pin.done());
+    }
+}
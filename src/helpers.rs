@@ -4,14 +4,18 @@ use crate::{
     el::El,
     event::Event,
     render::Renderer,
+    size::Length,
     widgets::{
         bar::{Bar, BarStyler},
         button::{Button, ButtonStyler},
         checkbox::{Checkbox, CheckboxStyler},
         container::{Container, ContainerStyler},
         divider::Divider,
+        grid::Grid,
         icon::IconStyler,
-        knob::{Knob, KnobStyler, KnobValue},
+        knob::{Knob, KnobEvent, KnobStyler, KnobValue},
+        overlay::Overlay,
+        router::Router,
         scrollable::{Scrollable, ScrollableStyler},
         select::{Select, SelectStyler},
         slider::{Slider, SliderPosition, SliderStyler},
@@ -31,6 +35,24 @@ pub fn container<'a, Message: Clone, R: Renderer, E: Event, S: ContainerStyler<R
     Container::new(content)
 }
 
+pub fn grid<'a, Message, R: Renderer, E: Event, S>(
+    columns: impl IntoIterator<Item = impl Into<Length>>,
+    rows: impl IntoIterator<Item = impl Into<Length>>,
+) -> Grid<'a, Message, R, E, S> {
+    Grid::new(columns, rows)
+}
+
+pub fn overlay<'a, Message, R: Renderer, E: Event, S>(
+    base: impl Into<El<'a, Message, R, E, S>>,
+    content: impl Into<El<'a, Message, R, E, S>>,
+) -> Overlay<'a, Message, R, E, S> {
+    Overlay::new(base, content)
+}
+
+pub fn router<'a, Message, R: Renderer, E: Event, S>() -> Router<'a, Message, R, E, S> {
+    Router::new()
+}
+
 pub fn text<'a, T: Display, R: Renderer, S: TextStyler<R::Color>>(
     content: impl Into<Text<'a, T, R, S>>,
 ) -> Text<'a, T, R, S> {
@@ -131,7 +153,7 @@ pub fn slider_h<'a, Message: Clone, R: Renderer, S: SliderStyler<R::Color>>(
 // ) -> Knob<'a, Message, R, E, S> { Knob::new(on_change)
 // }
 
-pub fn knob<'a, Message: Clone, R: Renderer, E: Event, S: KnobStyler<R::Color>>(
+pub fn knob<'a, Message: Clone, R: Renderer, E: KnobEvent, S: KnobStyler<R::Color>>(
     on_change: impl (Fn(KnobValue) -> Message) + 'a,
 ) -> Knob<'a, Message, R, E, S> {
     Knob::new(on_change)
@@ -18,7 +18,7 @@ use crate::{
 /// Positioning strategy, don't confuse with logic of CSS position.
 /// For now, [`Position::Relative`] means "relative to the parent".
 /// [`Position::Absolute`] is relative to viewport.
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, PartialEq)]
 pub enum Position {
     Relative,
     Absolute,
@@ -29,16 +29,63 @@ pub struct Viewport {
     pub size: Size,
 }
 
+/// Place of a [`Grid`](crate::widgets::grid::Grid) child, tracks are
+/// counted from zero
+#[derive(Clone, Copy)]
+pub struct Cell {
+    pub column: usize,
+    pub row: usize,
+    pub column_span: usize,
+    pub row_span: usize,
+    pub align_h: Align,
+    pub align_v: Align,
+}
+
+impl Cell {
+    pub fn new(column: usize, row: usize) -> Self {
+        Self {
+            column,
+            row,
+            column_span: 1,
+            row_span: 1,
+            align_h: Align::Start,
+            align_v: Align::Start,
+        }
+    }
+
+    /// Count of columns and rows the cell takes, at least one of each
+    pub fn span(mut self, columns: usize, rows: usize) -> Self {
+        self.column_span = columns.max(1);
+        self.row_span = rows.max(1);
+        self
+    }
+
+    /// Is the track crossing in the cell or its span
+    pub fn covers(&self, column: usize, row: usize) -> bool {
+        (self.column..self.column + self.column_span).contains(&column)
+            && (self.row..self.row + self.row_span).contains(&row)
+    }
+
+    /// Alignment of the child inside of the cell
+    pub fn align(mut self, horizontal: Align, vertical: Align) -> Self {
+        self.align_h = horizontal;
+        self.align_v = vertical;
+        self
+    }
+}
+
 // #[derive(Clone, Copy)]
 // pub struct Margin {
 
 // }
 
-#[derive(Clone)]
+#[derive(Clone, PartialEq)]
 pub struct LayoutNode {
     position: Position,
     bounds: Rectangle,
     content: Size,
+    /// Space between bounds and children
+    padding: Padding,
     children: Vec<LayoutNode>,
 }
 
@@ -48,6 +95,7 @@ impl LayoutNode {
             position: Position::Relative,
             bounds: Rectangle { top_left: Point::zero(), size: size.into() },
             content: size,
+            padding: Padding::zero(),
             children: vec![],
         }
     }
@@ -61,17 +109,22 @@ impl LayoutNode {
             position: Position::Relative,
             bounds: Rectangle { top_left: Point::zero(), size: size.into() },
             content: size - margin,
+            padding: Padding::zero(),
             children: children.into_iter().collect(),
         }
     }
 
-    // pub fn absolute(size: Size) -> Self {
-    //     Self {
-    //         position: Position::Absolute,
-    //         bounds: Rectangle { top_left: Point::zero(), size: size.into() },
-    //         children: vec![],
-    //     }
-    // }
+    /// Space between bounds and children, debug mode shows it
+    pub fn padded(mut self, padding: Padding) -> Self {
+        self.padding = padding;
+        self
+    }
+
+    /// Position node relative to viewport instead of the parent
+    pub fn absolute(mut self) -> Self {
+        self.position = Position::Absolute;
+        self
+    }
 
     pub fn position(&self) -> Position {
         self.position
@@ -111,7 +164,8 @@ impl LayoutNode {
                     (parent_size.height as i32 - self.bounds.size.height as i32) / 2;
             },
             Align::End => {
-                self.bounds.top_left.y += parent_size.width as i32 - self.bounds.size.width as i32;
+                self.bounds.top_left.y +=
+                    parent_size.height as i32 - self.bounds.size.height as i32;
             },
         }
 
@@ -179,6 +233,14 @@ impl<'a> Layout<'a> {
         Rectangle { top_left: self.viewport_position, size: self.node.bounds.size }
     }
 
+    /// Bounds without padding in viewport
+    pub fn content_bounds(&self) -> Rectangle {
+        Rectangle {
+            top_left: self.viewport_position + self.node.padding.top_left(),
+            size: (self.node.size() - self.node.padding).into(),
+        }
+    }
+
     pub fn sized(
         limits: &Limits,
         size: impl Into<Size<Length>>,
@@ -227,6 +289,7 @@ impl<'a> Layout<'a> {
         let content = content.moved(content_offset).aligned(content_align_h, content_align_v, size);
 
         LayoutNode::with_children(size.expand(fit_padding), box_model.margin, vec![content])
+            .padded(fit_padding)
     }
 
     pub fn flex<Message, R: Renderer, E: Event, S>(
@@ -388,9 +451,172 @@ impl<'a> Layout<'a> {
             limits.resolve_size(size.width, size.height, Size::new(content_width, content_height));
 
         LayoutNode::with_children(size.expand(padding), box_model.margin, layout_children)
+            .padded(padding)
+    }
+
+    /// Lay out `children` in `cells` of `columns` and `rows` tracks. Shrink
+    /// tracks fit children of cells spanning only them, fill and div tracks
+    /// share the remaining space as flex children do.
+    #[allow(clippy::too_many_arguments)]
+    pub fn grid<Message, R: Renderer, E: Event, S>(
+        ctx: &mut UiCtx<Message>,
+        state_tree: &mut StateNode,
+        styler: &S,
+        limits: &Limits,
+        size: impl Into<Size<Length>>,
+        position: Position,
+        viewport: &Viewport,
+        box_model: BoxModel,
+        columns: &[Length],
+        rows: &[Length],
+        gap: Size,
+        cells: &[Cell],
+        children: &[El<'_, Message, R, E, S>],
+    ) -> LayoutNode {
+        let size = size.into();
+        let padding = box_model.padding;
+
+        let limits = limits
+            .for_position(position, viewport)
+            .limit_width(size.width)
+            .limit_height(size.height)
+            .shrink(padding);
+
+        // Measure children of shrink tracks
+        let mut column_content = vec![0; columns.len()];
+        let mut row_content = vec![0; rows.len()];
+
+        for ((cell, child), child_state) in
+            cells.iter().zip(children).zip(state_tree.children.iter_mut())
+        {
+            let column = columns.get(cell.column).filter(|_| cell.column_span == 1);
+            let row = rows.get(cell.row).filter(|_| cell.row_span == 1);
+
+            let child_size = child.size(viewport);
+            let shrink_column = column == Some(&Length::Shrink) && !child_size.width.is_fill();
+            let shrink_row = row == Some(&Length::Shrink) && !child_size.height.is_fill();
+
+            if !shrink_column && !shrink_row {
+                continue;
+            }
+
+            let max = Size::new(
+                match column {
+                    Some(&Length::Fixed(width)) => width,
+                    _ => limits.max().width,
+                },
+                match row {
+                    Some(&Length::Fixed(height)) => height,
+                    _ => limits.max().height,
+                },
+            );
+            let measured = child
+                .layout(ctx, child_state, styler, &Limits::new(Size::zero(), max), viewport)
+                .size();
+
+            if shrink_column {
+                column_content[cell.column] = column_content[cell.column].max(measured.width);
+            }
+            if shrink_row {
+                row_content[cell.row] = row_content[cell.row].max(measured.height);
+            }
+        }
+
+        let column_sizes = grid_tracks(
+            columns,
+            &column_content,
+            limits.max().width,
+            gap.width,
+            size.width == Length::Shrink,
+        );
+        let row_sizes = grid_tracks(
+            rows,
+            &row_content,
+            limits.max().height,
+            gap.height,
+            size.height == Length::Shrink,
+        );
+
+        let layout_children = cells
+            .iter()
+            .zip(children)
+            .zip(state_tree.children.iter_mut())
+            .map(|((cell, child), child_state)| {
+                let (x, width) = grid_area(&column_sizes, gap.width, cell.column, cell.column_span);
+                let (y, height) = grid_area(&row_sizes, gap.height, cell.row, cell.row_span);
+                let area = Size::new(width, height);
+
+                child
+                    .layout(ctx, child_state, styler, &Limits::new(Size::zero(), area), viewport)
+                    .moved(padding.top_left() + Point::new(x as i32, y as i32))
+                    .aligned(cell.align_h, cell.align_v, area)
+            })
+            .collect::<Vec<_>>();
+
+        let (_, content_width) = grid_area(&column_sizes, gap.width, 0, column_sizes.len());
+        let (_, content_height) = grid_area(&row_sizes, gap.height, 0, row_sizes.len());
+        let size =
+            limits.resolve_size(size.width, size.height, Size::new(content_width, content_height));
+
+        LayoutNode::with_children(size.expand(padding), box_model.margin, layout_children)
+            .padded(padding)
     }
 }
 
+/// Sizes of grid tracks along an axis. Fill and div tracks share the space
+/// left in `max`, they are empty if the grid shrinks along the axis.
+fn grid_tracks(tracks: &[Length], content: &[u32], max: u32, gap: u32, shrink: bool) -> Vec<u32> {
+    let mut sizes = tracks
+        .iter()
+        .zip(content)
+        .map(|(track, &content)| match *track {
+            Length::Fixed(fixed) => fixed,
+            Length::Shrink => content,
+            Length::Fill | Length::Div(_) => 0,
+        })
+        .collect::<Vec<_>>();
+
+    let taken = sizes
+        .iter()
+        .fold(gap.saturating_mul(tracks.len().saturating_sub(1) as u32), |taken, &size| {
+            taken.saturating_add(size)
+        });
+    let free = if shrink { 0 } else { max.saturating_sub(taken) };
+
+    let total_divs = tracks.iter().map(|track| track.div_factor() as u32).sum::<u32>();
+    let div = free.checked_div(total_divs).unwrap_or(0);
+    let mut rem = free.checked_rem(total_divs).unwrap_or(0);
+
+    for (size, track) in sizes.iter_mut().zip(tracks) {
+        let divs = track.div_factor() as u32;
+
+        if divs != 0 {
+            *size = div * divs
+                + if rem > 0 {
+                    rem -= 1;
+                    1
+                } else {
+                    0
+                };
+        }
+    }
+
+    sizes
+}
+
+/// Offset and length of `span` tracks starting at `start`, with gaps between
+/// them. Tracks out of the grid are empty.
+fn grid_area(sizes: &[u32], gap: u32, start: usize, span: usize) -> (u32, u32) {
+    let start = start.min(sizes.len());
+    let end = start.saturating_add(span).min(sizes.len());
+
+    let offset = sizes[..start].iter().map(|size| size + gap).sum();
+    let length =
+        sizes[start..end].iter().sum::<u32>() + gap * (end - start).saturating_sub(1) as u32;
+
+    (offset, length)
+}
+
 #[derive(Clone, Copy)]
 pub struct Limits {
     min: Size<u32>,
@@ -507,3 +733,29 @@ impl From<Rectangle> for Limits {
         Self::new(Size::zero(), value.size.into())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use embedded_graphics::geometry::Point;
+
+    use crate::{align::Align, size::Size};
+
+    use super::LayoutNode;
+
+    #[test]
+    fn aligns_to_end_of_each_axis() {
+        let node = LayoutNode::childless(Size::new(4, 2)).aligned(
+            Align::End,
+            Align::End,
+            Size::new(20, 10),
+        );
+        assert_eq!(node.bounds.top_left, Point::new(16, 8));
+
+        let node = LayoutNode::childless(Size::new(4, 2)).aligned(
+            Align::Center,
+            Align::Center,
+            Size::new(20, 10),
+        );
+        assert_eq!(node.bounds.top_left, Point::new(8, 4));
+    }
+}
@@ -1,27 +1,39 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
+pub mod acceleration;
 pub mod action;
 pub mod align;
+pub mod animation;
 pub mod app;
 pub mod axis;
 pub mod block;
+pub mod collections;
 pub mod color;
 pub mod debug;
 pub mod el;
 pub mod event;
 pub mod focus;
 pub mod font;
+pub mod gesture;
+#[cfg(feature = "hal")]
+pub mod hal;
 pub mod helpers;
 pub mod icons;
 pub mod layout;
 mod log;
 pub mod padding;
 pub mod palette;
+pub mod queue;
+pub mod record;
 pub mod render;
 pub mod size;
 pub mod state;
 pub mod style;
+pub mod subscription;
+#[cfg(any(test, feature = "std"))]
+pub mod testing;
 pub mod theme;
+pub mod time;
 pub mod ui;
 pub mod widget;
 pub mod widgets;
@@ -29,9 +41,8 @@ pub mod widgets;
 #[cfg(feature = "simulator")]
 pub mod simulator;
 
-// TODO: Feature to switch to fixed-sized heapless
 #[macro_use]
 extern crate alloc;
 
-#[cfg(feature = "std")]
+#[cfg(any(test, feature = "std"))]
 extern crate std;
@@ -1,4 +1,5 @@
-#[cfg(all(not(feature = "std"), feature = "defmt"))]
+/// `defmt` is preferred when both `std` and `defmt` are enabled
+#[cfg(feature = "defmt")]
 pub mod logger {
     macro_rules! debug {
         ($($args: expr),* $(,)?) => {
@@ -6,7 +6,7 @@ use crate::{axis::Axis, size::Size};
 
 // TODO: Outline -- outer bounds border that does not take any space
 
-#[derive(Clone, Copy, Default)]
+#[derive(Clone, Copy, Default, PartialEq)]
 pub struct Padding {
     pub left: u32,
     pub right: u32,
@@ -0,0 +1,271 @@
+use core::{
+    cell::UnsafeCell,
+    mem::MaybeUninit,
+    sync::atomic::{AtomicI32, AtomicUsize, Ordering},
+};
+
+use crate::event::{CommonEvent, Event};
+
+/// Fixed-capacity single-producer single-consumer event queue, e.g. to push
+/// events from interrupts and drain them into [`UI::tick`](crate::ui::UI::tick)
+/// without allocations. Lock-free, only atomic loads and stores are used,
+/// so it works on cores without compare-and-swap too.
+///
+/// When the queue is full, [`CommonEvent::FocusMove`] offsets are summed
+/// and delivered as a single move right after the events queued before it,
+/// so fast encoder rotation is not lost and input keeps its order. Other
+/// events are dropped and counted.
+///
+/// Queue needs to outlive both ends. For interrupts get a `&'static mut`
+/// queue once, e.g. from a `StaticCell` of the `static_cell` crate or
+/// `cortex_m::singleton!`, and split it before enabling them:
+///
+/// ```ignore
+/// static QUEUE: StaticCell<EventQueue<Event, 16>> = StaticCell::new();
+///
+/// let (producer, consumer) = QUEUE.init(EventQueue::new()).split();
+/// ```
+///
+/// Then move the producer into the interrupt handler's resources.
+pub struct EventQueue<E: Event, const N: usize> {
+    slots: [UnsafeCell<MaybeUninit<E>>; N],
+    /// Position to pop at, written by consumer only. Positions go around
+    /// twice the capacity, so full and empty queues differ.
+    head: AtomicUsize,
+    /// Position to push at, written by producer only
+    tail: AtomicUsize,
+    /// Sum of moves which didn't fit, wrapping, written by producer only
+    moves: AtomicI32,
+    /// Part of `moves` consumer already took, written by consumer only.
+    /// Moves are pending while it differs from `moves`.
+    taken: AtomicI32,
+    /// Push position the pending move was coalesced at, it goes before the
+    /// event in this position. Written by producer only when nothing is
+    /// pending.
+    barrier: AtomicUsize,
+    dropped: AtomicUsize,
+}
+
+// SAFETY: Shared queue is only touched through atomics and slots, and
+// `split` taking `&mut self` ensures a single producer and a single consumer
+// at a time. Producer only writes slots in `tail..head + N`, which consumer
+// doesn't read, and consumer only reads slots in `head..tail`, which producer
+// doesn't write. Events are moved between threads, so they must be `Send`.
+unsafe impl<E: Event + Send, const N: usize> Sync for EventQueue<E, N> {}
+
+impl<E: Event, const N: usize> Default for EventQueue<E, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<E: Event, const N: usize> EventQueue<E, N> {
+    pub const fn new() -> Self {
+        const { assert!(N > 0, "Event queue capacity must not be zero") };
+
+        Self {
+            slots: [const { UnsafeCell::new(MaybeUninit::uninit()) }; N],
+            head: AtomicUsize::new(0),
+            tail: AtomicUsize::new(0),
+            moves: AtomicI32::new(0),
+            taken: AtomicI32::new(0),
+            barrier: AtomicUsize::new(0),
+            dropped: AtomicUsize::new(0),
+        }
+    }
+
+    fn len(head: usize, tail: usize) -> usize {
+        (tail + 2 * N - head) % (2 * N)
+    }
+
+    fn advance(position: usize) -> usize {
+        (position + 1) % (2 * N)
+    }
+
+    pub fn split(&mut self) -> (Producer<'_, E, N>, Consumer<'_, E, N>) {
+        (Producer { queue: self }, Consumer { queue: self })
+    }
+}
+
+impl<E: Event, const N: usize> Drop for EventQueue<E, N> {
+    fn drop(&mut self) {
+        let head = *self.head.get_mut();
+        let len = Self::len(head, *self.tail.get_mut());
+
+        for index in head..head + len {
+            // SAFETY: Slots in `head..tail` are initialized by `push` and not
+            // yet read by the consumer, `&mut self` means no end is alive
+            unsafe { self.slots[index % N].get_mut().assume_init_drop() };
+        }
+    }
+}
+
+/// Pushing end of [`EventQueue`], e.g. owned by an interrupt handler
+pub struct Producer<'a, E: Event, const N: usize> {
+    queue: &'a EventQueue<E, N>,
+}
+
+// SAFETY: Producer is the only pusher, see `Sync` of the queue
+unsafe impl<E: Event + Send, const N: usize> Send for Producer<'_, E, N> {}
+
+impl<E: Event, const N: usize> Producer<'_, E, N> {
+    /// Push an event, `false` if it is dropped because the queue is full.
+    /// Moves fail only if the queue got full again behind a pending move.
+    pub fn push(&mut self, event: E) -> bool {
+        let queue = self.queue;
+        let tail = queue.tail.load(Ordering::Relaxed);
+        let head = queue.head.load(Ordering::Acquire);
+        let moves = queue.moves.load(Ordering::Relaxed);
+        let pending = moves != queue.taken.load(Ordering::Acquire);
+
+        if let Some(CommonEvent::FocusMove(offset)) = event.as_common() {
+            // Pending move is still the last one in order, add to it
+            let coalesce = match pending {
+                true => tail == queue.barrier.load(Ordering::Relaxed),
+                false => EventQueue::<E, N>::len(head, tail) == N,
+            };
+
+            // Coalesced moves go before the event at `barrier`: consumer
+            // delivers them once its `head` reaches it. `barrier` is stored
+            // before the release store of `moves`, so consumer acquiring
+            // `moves` sees the barrier of the moves. It is only changed when
+            // nothing is pending, i.e. after consumer took all moves.
+            if coalesce {
+                if !pending {
+                    queue.barrier.store(tail, Ordering::Relaxed);
+                }
+                queue.moves.store(moves.wrapping_add(offset), Ordering::Release);
+                return true;
+            }
+        }
+
+        if EventQueue::<E, N>::len(head, tail) < N {
+            // SAFETY: Queue is not full, so the slot at `tail` is free: it was
+            // never written or the consumer already read it and released it by
+            // storing `head` past it, which the acquire load above sees. The
+            // consumer doesn't read it until the release store of `tail`
+            // below publishes the written event.
+            unsafe { (*queue.slots[tail % N].get()).write(event) };
+            queue.tail.store(EventQueue::<E, N>::advance(tail), Ordering::Release);
+            return true;
+        }
+
+        let dropped = queue.dropped.load(Ordering::Relaxed);
+        queue.dropped.store(dropped.wrapping_add(1), Ordering::Relaxed);
+        false
+    }
+}
+
+/// Draining end of [`EventQueue`], owned by the UI loop. Iterating it pops
+/// events until the queue is empty, pass `&mut consumer` to
+/// [`UI::tick`](crate::ui::UI::tick).
+pub struct Consumer<'a, E: Event, const N: usize> {
+    queue: &'a EventQueue<E, N>,
+}
+
+// SAFETY: Consumer is the only popper, see `Sync` of the queue
+unsafe impl<E: Event + Send, const N: usize> Send for Consumer<'_, E, N> {}
+
+impl<E: Event, const N: usize> Consumer<'_, E, N> {
+    /// Count of events dropped since the queue creation, wrapping
+    pub fn dropped(&self) -> usize {
+        self.queue.dropped.load(Ordering::Relaxed)
+    }
+}
+
+impl<E: Event, const N: usize> Iterator for Consumer<'_, E, N> {
+    type Item = E;
+
+    fn next(&mut self) -> Option<E> {
+        let queue = self.queue;
+        let head = queue.head.load(Ordering::Relaxed);
+        let tail = queue.tail.load(Ordering::Acquire);
+        let moves = queue.moves.load(Ordering::Acquire);
+        let taken = queue.taken.load(Ordering::Relaxed);
+
+        // Pending moves go right after the events queued before them, the
+        // barrier of pending moves is visible through the acquire of `moves`
+        if moves != taken && head == queue.barrier.load(Ordering::Relaxed) {
+            queue.taken.store(moves, Ordering::Release);
+            return Some(CommonEvent::FocusMove(moves.wrapping_sub(taken)).into());
+        }
+
+        if head == tail {
+            return None;
+        }
+
+        // SAFETY: `head != tail`, so the slot at `head` holds an event the
+        // producer wrote before its release store of `tail`, which the acquire
+        // load above sees. Producer doesn't reuse the slot until the release
+        // store of `head` below frees it, and the slot is read only once.
+        let event = unsafe { (*queue.slots[head % N].get()).assume_init_read() };
+        queue.head.store(EventQueue::<E, N>::advance(head), Ordering::Release);
+        Some(event)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use crate::{event::CommonEvent, testing::Event};
+
+    use super::EventQueue;
+
+    fn moves(events: impl Iterator<Item = Event>) -> Vec<i32> {
+        events
+            .map(|event| match event.0 {
+                CommonEvent::FocusMove(offset) => offset,
+                _ => 0,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn keeps_order_across_wrap() {
+        let mut queue = EventQueue::<Event, 3>::new();
+        let (mut producer, mut consumer) = queue.split();
+
+        for round in 0..5 {
+            assert!(producer.push(CommonEvent::FocusMove(round).into()));
+            assert!(producer.push(CommonEvent::FocusMove(round + 10).into()));
+            assert_eq!(moves(consumer.by_ref()), [round, round + 10]);
+        }
+    }
+
+    #[test]
+    fn coalesces_moves_on_overflow() {
+        let mut queue = EventQueue::<Event, 2>::new();
+        let (mut producer, mut consumer) = queue.split();
+
+        for offset in [1, 2, 3, -1, 4] {
+            assert!(producer.push(CommonEvent::FocusMove(offset).into()));
+        }
+        assert!(!producer.push(CommonEvent::Back.into()));
+
+        assert_eq!(moves(consumer.by_ref()), [1, 2, 6]);
+        assert_eq!(consumer.dropped(), 1);
+        assert!(consumer.next().is_none());
+    }
+
+    #[test]
+    fn delivers_coalesced_move_before_later_events() {
+        let mut queue = EventQueue::<Event, 2>::new();
+        let (mut producer, mut consumer) = queue.split();
+
+        assert!(producer.push(CommonEvent::Back.into()));
+        assert!(producer.push(CommonEvent::FocusMove(1).into()));
+        assert!(producer.push(CommonEvent::FocusMove(2).into()));
+        assert!(matches!(consumer.next(), Some(Event(CommonEvent::Back))));
+
+        // Space is free, but the press goes after the pending move
+        assert!(producer.push(CommonEvent::FocusButtonDown.into()));
+
+        let events: Vec<_> = consumer.by_ref().map(|event| event.0).collect();
+        assert!(matches!(
+            events[..],
+            [CommonEvent::FocusMove(1), CommonEvent::FocusMove(2), CommonEvent::FocusButtonDown]
+        ));
+        assert_eq!(consumer.dropped(), 0);
+    }
+}
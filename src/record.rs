@@ -0,0 +1,361 @@
+use core::{fmt, str::FromStr, time::Duration};
+
+use alloc::{string::String, vec::Vec};
+
+use crate::{
+    event::{CommonEvent, Controls, Event},
+    focus::Direction,
+    gesture::Gesture,
+    time::{Clock, Instant},
+};
+
+/// Event which can be written to a [`Log`] as a single line of text and read
+/// back. By default only the common part of event is recorded, events with
+/// other data must override both methods.
+pub trait Record: Event {
+    /// Fails if event can't be recorded, [`Log`] text has a comment line in
+    /// place of it
+    fn write(&self, f: &mut dyn fmt::Write) -> fmt::Result {
+        match self.as_common() {
+            Some(common) => write_common(common, f),
+            None => Err(fmt::Error),
+        }
+    }
+
+    fn read(text: &str) -> Option<Self> {
+        read_common(text).map(Self::from)
+    }
+}
+
+pub fn write_common(event: CommonEvent, f: &mut dyn fmt::Write) -> fmt::Result {
+    match event {
+        CommonEvent::FocusMove(offset) => write!(f, "move {offset}"),
+        CommonEvent::FocusDirection(direction) => write!(
+            f,
+            "direction {}",
+            match direction {
+                Direction::Up => "up",
+                Direction::Down => "down",
+                Direction::Left => "left",
+                Direction::Right => "right",
+            }
+        ),
+        CommonEvent::FocusButtonDown => f.write_str("press"),
+        CommonEvent::FocusButtonUp => f.write_str("release"),
+        CommonEvent::Exit => f.write_str("exit"),
+        CommonEvent::Back => f.write_str("back"),
+        CommonEvent::PointerDown(point) => write!(f, "pointer_down {} {}", point.x, point.y),
+        CommonEvent::PointerMove(point) => write!(f, "pointer_move {} {}", point.x, point.y),
+        CommonEvent::PointerUp(point) => write!(f, "pointer_up {} {}", point.x, point.y),
+        CommonEvent::Gesture(gesture) => write!(
+            f,
+            "gesture {}",
+            match gesture {
+                Gesture::LongPress => "long_press",
+                Gesture::DoublePress => "double_press",
+                Gesture::Repeat => "repeat",
+            }
+        ),
+        CommonEvent::ToggleDebug => f.write_str("debug"),
+    }
+}
+
+pub fn read_common(text: &str) -> Option<CommonEvent> {
+    let mut words = text.split_whitespace();
+    let name = words.next()?;
+    let mut number = || words.next()?.parse::<i32>().ok();
+
+    let event = match name {
+        "move" => CommonEvent::FocusMove(number()?),
+        "direction" => CommonEvent::FocusDirection(match words.next()? {
+            "up" => Direction::Up,
+            "down" => Direction::Down,
+            "left" => Direction::Left,
+            "right" => Direction::Right,
+            _ => return None,
+        }),
+        "press" => CommonEvent::FocusButtonDown,
+        "release" => CommonEvent::FocusButtonUp,
+        "exit" => CommonEvent::Exit,
+        "back" => CommonEvent::Back,
+        "debug" => CommonEvent::ToggleDebug,
+        "pointer_down" | "pointer_move" | "pointer_up" => {
+            let point = (number()?, number()?).into();
+            match name {
+                "pointer_down" => CommonEvent::PointerDown(point),
+                "pointer_move" => CommonEvent::PointerMove(point),
+                _ => CommonEvent::PointerUp(point),
+            }
+        },
+        "gesture" => CommonEvent::Gesture(match words.next()? {
+            "long_press" => Gesture::LongPress,
+            "double_press" => Gesture::DoublePress,
+            "repeat" => Gesture::Repeat,
+            _ => return None,
+        }),
+        _ => return None,
+    };
+
+    words.next().is_none().then_some(event)
+}
+
+/// Events with time passed since the recording start. As text it's a line
+/// per event, time in milliseconds followed by the event, e.g. `1200 move
+/// -1`. Empty lines and lines starting with `#` are skipped, events which
+/// can't be recorded are written as such comments.
+#[derive(Clone, Debug)]
+pub struct Log<E: Event> {
+    entries: Vec<(Duration, E)>,
+}
+
+impl<E: Event> Default for Log<E> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<E: Event> Log<E> {
+    pub fn new() -> Self {
+        Self { entries: Vec::new() }
+    }
+
+    /// Add event happened `at` after the start, entries must come in time
+    /// order
+    pub fn push(&mut self, at: Duration, event: E) {
+        self.entries.push((at, event));
+    }
+
+    pub fn entries(&self) -> &[(Duration, E)] {
+        &self.entries
+    }
+}
+
+impl<E: Record> fmt::Display for Log<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut line = String::new();
+        for (at, event) in &self.entries {
+            // Writing to a string can't fail, so error is from the event
+            line.clear();
+            match event.write(&mut line) {
+                Ok(()) => writeln!(f, "{} {line}", at.as_millis())?,
+                Err(_) => writeln!(f, "# {} not recorded: {event:?}", at.as_millis())?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Line of [`Log`] text which couldn't be parsed, counting from 1
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: usize,
+}
+
+impl<E: Record> FromStr for Log<E> {
+    type Err = ParseError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let mut log = Self::new();
+
+        for (index, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let entry = line.split_once(' ').and_then(|(millis, event)| {
+                Some((Duration::from_millis(millis.parse().ok()?), E::read(event)?))
+            });
+
+            match entry {
+                Some((at, event)) => log.push(at, event),
+                None => return Err(ParseError { line: index + 1 }),
+            }
+        }
+
+        Ok(log)
+    }
+}
+
+/// [`Controls`] logging events of the wrapped ones with time from `clock`
+pub struct Recorder<C: Controls<E>, E: Event, K: Clock> {
+    controls: C,
+    clock: K,
+    start: Instant,
+    log: Log<E>,
+}
+
+impl<C: Controls<E>, E: Event, K: Clock> Recorder<C, E, K> {
+    /// Recording starts now
+    pub fn new(controls: C, clock: K) -> Self {
+        Self { controls, start: clock.now(), clock, log: Log::new() }
+    }
+
+    pub fn log(&self) -> &Log<E> {
+        &self.log
+    }
+
+    /// Take the recorded log, recording continues into an empty one
+    pub fn take_log(&mut self) -> Log<E> {
+        core::mem::take(&mut self.log)
+    }
+}
+
+impl<C: Controls<E>, E: Event, K: Clock> Controls<E> for Recorder<C, E, K> {
+    fn events(&mut self) -> Vec<E> {
+        let events = self.controls.events();
+        let at = self.clock.now().duration_since(self.start);
+
+        for event in &events {
+            self.log.push(at, event.clone());
+        }
+
+        events
+    }
+}
+
+/// [`Controls`] giving events of a [`Log`] when their time comes by `clock`
+pub struct Replayer<E: Event, K: Clock> {
+    log: Log<E>,
+    clock: K,
+    start: Instant,
+    next: usize,
+}
+
+impl<E: Event, K: Clock> Replayer<E, K> {
+    /// Replay starts now
+    pub fn new(log: Log<E>, clock: K) -> Self {
+        Self { log, start: clock.now(), clock, next: 0 }
+    }
+
+    /// Time of the next event, to sleep until it
+    pub fn next_deadline(&self) -> Option<Instant> {
+        self.log.entries.get(self.next).map(|&(at, _)| self.start + at)
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.next == self.log.entries.len()
+    }
+}
+
+impl<E: Event, K: Clock> Controls<E> for Replayer<E, K> {
+    fn events(&mut self) -> Vec<E> {
+        let now = self.clock.now().duration_since(self.start);
+        let due = self.log.entries[self.next..].iter().take_while(|&&(at, _)| at <= now);
+        let events: Vec<E> = due.map(|(_, event)| event.clone()).collect();
+        self.next += events.len();
+
+        events
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::{rc::Rc, string::ToString, vec::Vec};
+    use core::time::Duration;
+
+    use embedded_graphics::{geometry::Size, pixelcolor::Rgb888};
+
+    use crate::{
+        event::{CommonEvent, Controls},
+        helpers::checkbox,
+        testing::{Event, Harness},
+        theme::Theme,
+        time::{Instant, ManualClock},
+    };
+
+    use super::{Log, ParseError, Record, Recorder, Replayer};
+
+    const TEXT: &str = "# Checkbox clicks\n\
+                        0 press\n\
+                        40 release\n\
+                        \n\
+                        300 pointer_down 2 3\n\
+                        350 pointer_up 2 3\n";
+
+    fn commons(events: impl IntoIterator<Item = Event>) -> Vec<CommonEvent> {
+        events.into_iter().map(|event| event.0).collect()
+    }
+
+    #[test]
+    fn text_round_trip() {
+        let log: Log<Event> = TEXT.parse().unwrap();
+
+        assert_eq!(log.entries().len(), 4);
+        assert_eq!(log.to_string().parse::<Log<Event>>().unwrap().to_string(), log.to_string());
+        assert_eq!("0 press\n10 jump".parse::<Log<Event>>().unwrap_err(), ParseError { line: 2 });
+    }
+
+    #[test]
+    fn skips_events_not_recorded() {
+        #[derive(Clone, Debug)]
+        enum Custom {
+            Common(CommonEvent),
+            Key,
+        }
+
+        impl From<CommonEvent> for Custom {
+            fn from(value: CommonEvent) -> Self {
+                Self::Common(value)
+            }
+        }
+
+        impl crate::event::Event for Custom {
+            fn as_common(&self) -> Option<CommonEvent> {
+                match self {
+                    Self::Common(common) => Some(*common),
+                    Self::Key => None,
+                }
+            }
+        }
+
+        impl Record for Custom {}
+
+        let mut log = Log::new();
+        log.push(Duration::ZERO, Custom::Key);
+        log.push(Duration::from_millis(10), CommonEvent::Back.into());
+
+        let text = log.to_string();
+        assert_eq!(text, "# 0 not recorded: Key\n10 back\n");
+        assert_eq!(text.parse::<Log<Custom>>().unwrap().entries().len(), 1);
+    }
+
+    #[test]
+    fn records_and_replays_in_time() {
+        let clock = Rc::new(ManualClock::new(Instant::from_millis(1000)));
+        let mut script =
+            [vec![CommonEvent::FocusMove(1).into()], vec![], vec![CommonEvent::Back.into()]]
+                .into_iter();
+        let mut recorder = Recorder::new(move || script.next().unwrap_or_default(), clock.clone());
+
+        for _ in 0..3 {
+            recorder.events();
+            clock.advance(Duration::from_millis(100));
+        }
+        let log = recorder.take_log();
+        assert_eq!(log.to_string(), "0 move 1\n200 back\n");
+
+        let mut replayer = Replayer::new(log, clock.clone());
+        assert!(matches!(commons(replayer.events())[..], [CommonEvent::FocusMove(1)]));
+        assert_eq!(replayer.next_deadline(), Some(Instant::from_millis(1500)));
+
+        clock.advance(Duration::from_millis(150));
+        assert!(replayer.events().is_empty());
+        clock.advance(Duration::from_millis(50));
+        assert!(matches!(commons(replayer.events())[..], [CommonEvent::Back]));
+        assert!(replayer.is_finished());
+    }
+
+    #[test]
+    fn replays_in_harness() {
+        let log: Log<Event> = TEXT.parse().unwrap();
+
+        let harness: Harness<_, Rgb888, _, Theme<_>> =
+            Harness::new(checkbox(|checked| checked), Size::new(32, 16));
+        let mut harness = harness.replay(&log);
+
+        // Focus button does nothing until pointer focuses the checkbox
+        assert_eq!(harness.messages(), [true]);
+    }
+}
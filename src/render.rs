@@ -116,6 +116,16 @@ impl<C: UiColor> DrawTargetRenderer<C> {
         }
     }
 
+    /// Renderer drawing only inside `bounds`, [`DrawTargetRenderer::finish`]
+    /// flushes only this region
+    pub fn region(bounds: Rectangle, default_bg: C) -> Self {
+        Self {
+            layers: vec![LayerKind::Normal],
+            result: CanvasAt::with_default_color(bounds.top_left, bounds.size, default_bg),
+            color: PhantomData,
+        }
+    }
+
     pub fn finish<D>(self, target: &mut D)
     where
         D::Error: core::fmt::Debug,
@@ -216,3 +226,37 @@ impl<C: UiColor> Renderer for DrawTargetRenderer<C> {
         image.draw(self).unwrap();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use embedded_graphics::{
+        geometry::{Point, Size},
+        pixelcolor::{Rgb888, RgbColor},
+        primitives::{Primitive, PrimitiveStyle, Rectangle},
+        Drawable,
+    };
+
+    use crate::testing::Framebuffer;
+
+    use super::DrawTargetRenderer;
+
+    #[test]
+    fn region_flushes_only_its_bounds() {
+        let mut frame = Framebuffer::new(Size::new(8, 8), Rgb888::BLUE);
+        let mut renderer = DrawTargetRenderer::region(
+            Rectangle::new(Point::new(2, 2), Size::new(4, 4)),
+            Rgb888::BLACK,
+        );
+
+        Rectangle::new(Point::zero(), Size::new(8, 8))
+            .into_styled(PrimitiveStyle::with_fill(Rgb888::RED))
+            .draw(&mut renderer)
+            .unwrap();
+        renderer.finish(&mut frame);
+
+        assert_eq!(frame.pixel(Point::new(1, 1)), Some(Rgb888::BLUE));
+        assert_eq!(frame.pixel(Point::new(2, 2)), Some(Rgb888::RED));
+        assert_eq!(frame.pixel(Point::new(5, 5)), Some(Rgb888::RED));
+        assert_eq!(frame.pixel(Point::new(6, 6)), Some(Rgb888::BLUE));
+    }
+}
@@ -1,6 +1,91 @@
+use alloc::vec::Vec;
+use embedded_graphics_simulator::{sdl2::Keycode, SimulatorEvent};
+
+use crate::{event::CommonEvent, focus::Direction};
+
+/// Keyboard keys bound to inputs of a simulated control, so the simulator
+/// matches the hardware. Each profile has its own inputs, a default map and
+/// `KeyMap::event` converting window events. Keep the map for the whole
+/// run, e.g.
+/// `runtime.tick(window.events().filter_map(|event| keys.event(event)))`.
+#[derive(Clone, Debug)]
+pub struct KeyMap<I> {
+    bindings: Vec<(Keycode, I)>,
+}
+
+impl<I: Copy> KeyMap<I> {
+    /// Map without keys
+    pub fn new() -> Self {
+        Self { bindings: Vec::new() }
+    }
+
+    /// Bind `key` to `input` replacing its previous binding. Several keys
+    /// can be bound to the same input.
+    pub fn bind(self, key: Keycode, input: I) -> Self {
+        let mut map = self.unbind(key);
+        map.bindings.push((key, input));
+        map
+    }
+
+    pub fn unbind(mut self, key: Keycode) -> Self {
+        self.bindings.retain(|&(bound, _)| bound != key);
+        self
+    }
+
+    pub fn input(&self, key: Keycode) -> Option<I> {
+        self.bindings.iter().find(|&&(bound, _)| bound == key).map(|&(_, input)| input)
+    }
+
+    /// Input of the key event and how the key changed
+    fn key(&self, event: &SimulatorEvent) -> Option<(I, KeyChange)> {
+        match *event {
+            SimulatorEvent::KeyDown { keycode, repeat, .. } => Some((
+                self.input(keycode)?,
+                if repeat { KeyChange::Repeat } else { KeyChange::Down },
+            )),
+            SimulatorEvent::KeyUp { keycode, .. } => Some((self.input(keycode)?, KeyChange::Up)),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum KeyChange {
+    Down,
+    /// Key is held and the system repeats it
+    Repeat,
+    Up,
+}
+
+/// F12 toggles UI debug mode in all profiles, see
+/// [`UI::debug`](crate::ui::UI::debug)
+fn is_debug_toggle(event: &SimulatorEvent) -> bool {
+    matches!(event, SimulatorEvent::KeyDown { keycode: Keycode::F12, repeat: false, .. })
+}
+
+/// Value shift by directional focus events, down and right increase
+fn direction_shift(event: CommonEvent) -> Option<i32> {
+    match event {
+        CommonEvent::FocusDirection(Direction::Down | Direction::Right) => Some(1),
+        CommonEvent::FocusDirection(Direction::Up | Direction::Left) => Some(-1),
+        CommonEvent::FocusMove(offset) => Some(offset),
+        _ => None,
+    }
+}
+
 pub mod single_encoder {
-    use crate::event::CommonEvent;
-    use embedded_graphics_simulator::sdl2::{self, Keycode, MouseButton};
+    use crate::{
+        event::{CommonEvent, KnobEvent, ScrollEvent, SelectEvent, SliderEvent},
+        focus::Direction,
+        gesture::Gesture,
+        record::{read_common, Record},
+    };
+    use embedded_graphics_simulator::{
+        sdl2::{self, Keycode, MouseButton},
+        SimulatorEvent,
+    };
+
+    use super::{is_debug_toggle, KeyChange, KeyMap};
 
     #[derive(Clone, Copy, Debug)]
     pub enum Event {
@@ -8,47 +93,68 @@ pub mod single_encoder {
         EncoderButtonDown,
         EncoderButtonUp,
         Exit,
+        Back,
+        Gesture(Gesture),
+        ToggleDebug,
     }
 
     impl From<CommonEvent> for Event {
         fn from(value: CommonEvent) -> Self {
             match value {
                 CommonEvent::FocusMove(offset) => Self::EncoderRotation(offset),
+                // Single encoder has no directions, move focus linearly
+                CommonEvent::FocusDirection(Direction::Up | Direction::Left) => {
+                    Self::EncoderRotation(-1)
+                },
+                CommonEvent::FocusDirection(Direction::Down | Direction::Right) => {
+                    Self::EncoderRotation(1)
+                },
                 CommonEvent::FocusButtonDown => Self::EncoderButtonDown,
                 CommonEvent::FocusButtonUp => Self::EncoderButtonUp,
                 CommonEvent::Exit => Self::Exit,
+                CommonEvent::Back => Self::Back,
+                // No pointer, press is the closest. Conversion can't fail,
+                // so moves become a no-op rotation, `Record::read` drops
+                // them instead.
+                CommonEvent::PointerDown(_) => Self::EncoderButtonDown,
+                CommonEvent::PointerMove(_) => Self::EncoderRotation(0),
+                CommonEvent::PointerUp(_) => Self::EncoderButtonUp,
+                CommonEvent::Gesture(gesture) => Self::Gesture(gesture),
+                CommonEvent::ToggleDebug => Self::ToggleDebug,
             }
         }
     }
 
-    impl TryFrom<embedded_graphics_simulator::SimulatorEvent> for Event {
-        type Error = ();
+    /// Controls of the encoder
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    pub enum Input {
+        RotateLeft,
+        RotateRight,
+        Press,
+        Back,
+    }
 
-        fn try_from(
-            value: embedded_graphics_simulator::SimulatorEvent,
-        ) -> Result<Self, Self::Error> {
-            match value {
-                embedded_graphics_simulator::SimulatorEvent::KeyDown { keycode, .. } => {
-                    match keycode {
-                        Keycode::Space | Keycode::Return | Keycode::Return2 => {
-                            Ok(Event::EncoderButtonDown)
-                        },
-                        _ => Err(()),
-                    }
-                },
-                embedded_graphics_simulator::SimulatorEvent::KeyUp { keycode, .. } => match keycode
-                {
-                    Keycode::Left | Keycode::Up => Ok(Event::EncoderRotation(-1)),
-                    Keycode::Right | Keycode::Down => Ok(Event::EncoderRotation(1)),
-                    Keycode::Space | Keycode::Return | Keycode::Return2 => {
-                        Ok(Event::EncoderButtonUp)
-                    },
-                    _ => Err(()),
-                },
-                embedded_graphics_simulator::SimulatorEvent::MouseWheel {
-                    scroll_delta,
-                    direction,
-                } => {
+    impl Default for KeyMap<Input> {
+        fn default() -> Self {
+            KeyMap::new()
+                .bind(Keycode::Left, Input::RotateLeft)
+                .bind(Keycode::Up, Input::RotateLeft)
+                .bind(Keycode::Right, Input::RotateRight)
+                .bind(Keycode::Down, Input::RotateRight)
+                .bind(Keycode::Space, Input::Press)
+                .bind(Keycode::Return, Input::Press)
+                .bind(Keycode::Return2, Input::Press)
+                .bind(Keycode::Escape, Input::Back)
+                .bind(Keycode::Backspace, Input::Back)
+        }
+    }
+
+    impl KeyMap<Input> {
+        /// Mouse wheel rotates the encoder and left or middle button
+        /// presses it. Keys rotate it on release.
+        pub fn event(&self, event: SimulatorEvent) -> Option<Event> {
+            match event {
+                SimulatorEvent::MouseWheel { scroll_delta, direction } => {
                     let dir = match direction {
                         sdl2::MouseWheelDirection::Normal => 1,
                         sdl2::MouseWheelDirection::Flipped => -1,
@@ -59,20 +165,28 @@ pub mod single_encoder {
 
                     let offset = scroll_delta.y * dir;
 
-                    Ok(Event::EncoderRotation(offset))
+                    Some(Event::EncoderRotation(offset))
+                },
+                SimulatorEvent::MouseButtonDown { mouse_btn, .. }
+                    if mouse_btn == MouseButton::Middle || mouse_btn == MouseButton::Left =>
+                {
+                    Some(Event::EncoderButtonDown)
                 },
-                embedded_graphics_simulator::SimulatorEvent::MouseButtonDown {
-                    mouse_btn, ..
-                } if mouse_btn == MouseButton::Middle || mouse_btn == MouseButton::Left => {
-                    Ok(Event::EncoderButtonDown)
+                SimulatorEvent::MouseButtonUp { mouse_btn, .. }
+                    if mouse_btn == MouseButton::Middle || mouse_btn == MouseButton::Left =>
+                {
+                    Some(Event::EncoderButtonUp)
                 },
-                embedded_graphics_simulator::SimulatorEvent::MouseButtonUp {
-                    mouse_btn, ..
-                } if mouse_btn == MouseButton::Middle || mouse_btn == MouseButton::Left => {
-                    Ok(Event::EncoderButtonUp)
+                SimulatorEvent::Quit => Some(Event::Exit),
+                _ if is_debug_toggle(&event) => Some(Event::ToggleDebug),
+                _ => match self.key(&event)? {
+                    (Input::Press, KeyChange::Down) => Some(Event::EncoderButtonDown),
+                    (Input::Press, KeyChange::Up) => Some(Event::EncoderButtonUp),
+                    (Input::RotateLeft, KeyChange::Up) => Some(Event::EncoderRotation(-1)),
+                    (Input::RotateRight, KeyChange::Up) => Some(Event::EncoderRotation(1)),
+                    (Input::Back, KeyChange::Up) => Some(Event::Back),
+                    _ => None,
                 },
-                embedded_graphics_simulator::SimulatorEvent::Quit => Ok(Event::Exit),
-                _ => Err(()),
             }
         }
     }
@@ -84,42 +198,629 @@ pub mod single_encoder {
                 Event::EncoderButtonDown => Some(CommonEvent::FocusButtonDown),
                 Event::EncoderButtonUp => Some(CommonEvent::FocusButtonUp),
                 Event::Exit => Some(CommonEvent::Exit),
+                Event::Back => Some(CommonEvent::Back),
+                Event::Gesture(gesture) => Some(CommonEvent::Gesture(*gesture)),
+                Event::ToggleDebug => Some(CommonEvent::ToggleDebug),
             }
         }
+    }
 
-        fn as_select_shift(&self) -> Option<i32> {
-            match self {
-                &Event::EncoderRotation(offset) => Some(offset),
-                _ => None,
+    impl SelectEvent for Event {}
+    impl SliderEvent for Event {}
+    impl KnobEvent for Event {}
+
+    /// Pointer moves of recorded logs, e.g. of touch profile, are dropped
+    impl Record for Event {
+        fn read(text: &str) -> Option<Self> {
+            match read_common(text)? {
+                CommonEvent::PointerMove(_) => None,
+                common => Some(common.into()),
             }
         }
+    }
 
-        fn as_slider_shift(&self) -> Option<i32> {
+    impl ScrollEvent for Event {
+        /// One encoder step scrolls by more than a pixel
+        fn as_scroll_offset(&self) -> Option<i32> {
             match self {
-                &Event::EncoderRotation(offset) => Some(offset),
+                &Event::EncoderRotation(offset) => Some(offset * 5),
                 _ => None,
             }
         }
+    }
+}
+
+/// Mouse acts as a touch panel, keyboard moves focus
+pub mod touch {
+    use crate::{
+        event::{CommonEvent, KnobEvent, ScrollEvent, SelectEvent, SliderEvent},
+        record::Record,
+    };
+    use embedded_graphics_simulator::{
+        sdl2::{Keycode, MouseButton},
+        SimulatorEvent,
+    };
 
+    use super::{is_debug_toggle, KeyChange, KeyMap};
+
+    #[derive(Clone, Copy, Debug)]
+    pub struct Event(pub CommonEvent);
+
+    impl From<CommonEvent> for Event {
+        fn from(value: CommonEvent) -> Self {
+            Self(value)
+        }
+    }
+
+    /// Buttons next to the touch panel
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    pub enum Input {
+        Previous,
+        Next,
+        Press,
+        Back,
+    }
+
+    impl Default for KeyMap<Input> {
+        fn default() -> Self {
+            KeyMap::new()
+                .bind(Keycode::Left, Input::Previous)
+                .bind(Keycode::Up, Input::Previous)
+                .bind(Keycode::Right, Input::Next)
+                .bind(Keycode::Down, Input::Next)
+                .bind(Keycode::Space, Input::Press)
+                .bind(Keycode::Return, Input::Press)
+                .bind(Keycode::Return2, Input::Press)
+                .bind(Keycode::Escape, Input::Back)
+                .bind(Keycode::Backspace, Input::Back)
+        }
+    }
+
+    impl KeyMap<Input> {
+        /// Left mouse button is the touch, keys move focus on release
+        pub fn event(&self, event: SimulatorEvent) -> Option<Event> {
+            let common = match event {
+                _ if is_debug_toggle(&event) => CommonEvent::ToggleDebug,
+                SimulatorEvent::MouseButtonDown { mouse_btn: MouseButton::Left, point } => {
+                    CommonEvent::PointerDown(point)
+                },
+                SimulatorEvent::MouseMove { point } => CommonEvent::PointerMove(point),
+                SimulatorEvent::MouseButtonUp { mouse_btn: MouseButton::Left, point } => {
+                    CommonEvent::PointerUp(point)
+                },
+                SimulatorEvent::Quit => CommonEvent::Exit,
+                _ => match self.key(&event)? {
+                    (Input::Press, KeyChange::Down) => CommonEvent::FocusButtonDown,
+                    (Input::Press, KeyChange::Up) => CommonEvent::FocusButtonUp,
+                    (Input::Previous, KeyChange::Up) => CommonEvent::FocusMove(-1),
+                    (Input::Next, KeyChange::Up) => CommonEvent::FocusMove(1),
+                    (Input::Back, KeyChange::Up) => CommonEvent::Back,
+                    _ => return None,
+                },
+            };
+
+            Some(Event(common))
+        }
+    }
+
+    impl crate::event::Event for Event {
+        fn as_common(&self) -> Option<CommonEvent> {
+            Some(self.0)
+        }
+    }
+
+    impl SelectEvent for Event {}
+    impl SliderEvent for Event {}
+    impl KnobEvent for Event {}
+    impl ScrollEvent for Event {}
+    impl Record for Event {}
+}
+
+/// D-pad with four direction buttons, center button and back. Unbind
+/// [`Input::Center`](dpad::Input::Center) for four-button pads. Directions
+/// move focus spatially and change values of active widgets.
+pub mod dpad {
+    use crate::{
+        event::{CommonEvent, KnobEvent, ScrollEvent, SelectEvent, SliderEvent},
+        focus::Direction,
+        record::Record,
+    };
+    use embedded_graphics_simulator::{sdl2::Keycode, SimulatorEvent};
+
+    use super::{direction_shift, is_debug_toggle, KeyChange, KeyMap};
+
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    pub enum Input {
+        Up,
+        Down,
+        Left,
+        Right,
+        Center,
+        Back,
+    }
+
+    impl Default for KeyMap<Input> {
+        fn default() -> Self {
+            KeyMap::new()
+                .bind(Keycode::Up, Input::Up)
+                .bind(Keycode::Down, Input::Down)
+                .bind(Keycode::Left, Input::Left)
+                .bind(Keycode::Right, Input::Right)
+                .bind(Keycode::Return, Input::Center)
+                .bind(Keycode::Space, Input::Center)
+                .bind(Keycode::Backspace, Input::Back)
+        }
+    }
+
+    impl KeyMap<Input> {
+        /// Held direction keys repeat
+        pub fn event(&self, event: SimulatorEvent) -> Option<Event> {
+            if let SimulatorEvent::Quit = event {
+                return Some(Event(CommonEvent::Exit));
+            }
+            if is_debug_toggle(&event) {
+                return Some(Event(CommonEvent::ToggleDebug));
+            }
+
+            let (input, change) = self.key(&event)?;
+
+            let direction = match input {
+                Input::Center => {
+                    return match change {
+                        KeyChange::Down => Some(Event(CommonEvent::FocusButtonDown)),
+                        KeyChange::Up => Some(Event(CommonEvent::FocusButtonUp)),
+                        KeyChange::Repeat => None,
+                    }
+                },
+                Input::Back => {
+                    return (change == KeyChange::Down).then_some(Event(CommonEvent::Back))
+                },
+                Input::Up => Direction::Up,
+                Input::Down => Direction::Down,
+                Input::Left => Direction::Left,
+                Input::Right => Direction::Right,
+            };
+
+            (change != KeyChange::Up).then_some(Event(CommonEvent::FocusDirection(direction)))
+        }
+    }
+
+    /// Events of directional controls, also used by
+    /// [`keyboard`](super::keyboard)
+    #[derive(Clone, Copy, Debug)]
+    pub struct Event(pub CommonEvent);
+
+    impl From<CommonEvent> for Event {
+        fn from(value: CommonEvent) -> Self {
+            Self(value)
+        }
+    }
+
+    impl crate::event::Event for Event {
+        fn as_common(&self) -> Option<CommonEvent> {
+            Some(self.0)
+        }
+    }
+
+    impl SelectEvent for Event {
+        fn as_select_shift(&self) -> Option<i32> {
+            direction_shift(self.0)
+        }
+    }
+
+    impl SliderEvent for Event {
+        fn as_slider_shift(&self) -> Option<i32> {
+            direction_shift(self.0)
+        }
+    }
+
+    impl KnobEvent for Event {
+        /// Up turns the knob clockwise as right does
         fn as_knob_rotation(&self) -> Option<i32> {
-            match self {
-                &Event::EncoderRotation(offset) => Some(offset),
-                _ => None,
+            match self.0 {
+                CommonEvent::FocusDirection(Direction::Up) => Some(1),
+                CommonEvent::FocusDirection(Direction::Down) => Some(-1),
+                common => direction_shift(common),
             }
         }
+    }
 
-        fn as_input_letter_scroll(&self) -> Option<i32> {
-            match self {
-                &Event::EncoderRotation(offset) => Some(offset),
-                _ => None,
+    impl ScrollEvent for Event {
+        fn as_scroll_offset(&self) -> Option<i32> {
+            direction_shift(self.0).map(|shift| shift * 5)
+        }
+    }
+
+    impl Record for Event {}
+}
+
+/// Two encoders, one moves focus and the other changes values of active
+/// widgets. Button of the value encoder goes back.
+pub mod dual_encoder {
+    use core::fmt;
+
+    use crate::{
+        event::{CommonEvent, KnobEvent, ScrollEvent, SelectEvent, SliderEvent},
+        record::{read_common, write_common, Record},
+    };
+    use embedded_graphics_simulator::{
+        sdl2::{self, Keycode},
+        SimulatorEvent,
+    };
+
+    use super::{is_debug_toggle, KeyChange, KeyMap};
+
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    pub enum Input {
+        NavigationLeft,
+        NavigationRight,
+        NavigationPress,
+        ValueLeft,
+        ValueRight,
+        ValuePress,
+    }
+
+    impl Default for KeyMap<Input> {
+        fn default() -> Self {
+            KeyMap::new()
+                .bind(Keycode::Left, Input::NavigationLeft)
+                .bind(Keycode::Right, Input::NavigationRight)
+                .bind(Keycode::Return, Input::NavigationPress)
+                .bind(Keycode::Space, Input::NavigationPress)
+                .bind(Keycode::A, Input::ValueLeft)
+                .bind(Keycode::D, Input::ValueRight)
+                .bind(Keycode::S, Input::ValuePress)
+        }
+    }
+
+    impl KeyMap<Input> {
+        /// Mouse wheel rotates the value encoder, held rotation keys repeat
+        pub fn event(&self, event: SimulatorEvent) -> Option<Event> {
+            match event {
+                SimulatorEvent::MouseWheel { scroll_delta, direction } => {
+                    let dir = match direction {
+                        sdl2::MouseWheelDirection::Flipped => -1,
+                        _ => 1,
+                    };
+
+                    Some(Event::Value(scroll_delta.y * dir))
+                },
+                SimulatorEvent::Quit => Some(Event::Common(CommonEvent::Exit)),
+                _ if is_debug_toggle(&event) => Some(Event::Common(CommonEvent::ToggleDebug)),
+                _ => {
+                    let event = match self.key(&event)? {
+                        (Input::NavigationPress, KeyChange::Down) => {
+                            Event::Common(CommonEvent::FocusButtonDown)
+                        },
+                        (Input::NavigationPress, KeyChange::Up) => {
+                            Event::Common(CommonEvent::FocusButtonUp)
+                        },
+                        (Input::ValuePress, KeyChange::Down) => Event::Common(CommonEvent::Back),
+                        (_, KeyChange::Up) | (Input::NavigationPress | Input::ValuePress, _) => {
+                            return None
+                        },
+                        (Input::NavigationLeft, _) => Event::Common(CommonEvent::FocusMove(-1)),
+                        (Input::NavigationRight, _) => Event::Common(CommonEvent::FocusMove(1)),
+                        (Input::ValueLeft, _) => Event::Value(-1),
+                        (Input::ValueRight, _) => Event::Value(1),
+                    };
+
+                    Some(event)
+                },
             }
         }
+    }
 
+    #[derive(Clone, Copy, Debug)]
+    pub enum Event {
+        /// Navigation encoder and buttons
+        Common(CommonEvent),
+        /// Value encoder rotation
+        Value(i32),
+    }
+
+    impl Event {
+        fn value(&self) -> Option<i32> {
+            match *self {
+                Event::Value(offset) => Some(offset),
+                Event::Common(_) => None,
+            }
+        }
+    }
+
+    impl From<CommonEvent> for Event {
+        fn from(value: CommonEvent) -> Self {
+            Self::Common(value)
+        }
+    }
+
+    impl crate::event::Event for Event {
+        fn as_common(&self) -> Option<CommonEvent> {
+            match *self {
+                Event::Common(common) => Some(common),
+                Event::Value(_) => None,
+            }
+        }
+    }
+
+    impl SelectEvent for Event {
+        fn as_select_shift(&self) -> Option<i32> {
+            self.value()
+        }
+    }
+
+    impl SliderEvent for Event {
+        fn as_slider_shift(&self) -> Option<i32> {
+            self.value()
+        }
+    }
+
+    impl KnobEvent for Event {
+        fn as_knob_rotation(&self) -> Option<i32> {
+            self.value()
+        }
+    }
+
+    impl ScrollEvent for Event {
         fn as_scroll_offset(&self) -> Option<i32> {
-            match self {
-                &Event::EncoderRotation(offset) => Some(offset * 5),
-                _ => None,
+            self.value().map(|offset| offset * 5)
+        }
+    }
+
+    /// Value encoder rotation is recorded as `value <offset>`
+    impl Record for Event {
+        fn write(&self, f: &mut dyn fmt::Write) -> fmt::Result {
+            match *self {
+                Event::Common(common) => write_common(common, f),
+                Event::Value(offset) => write!(f, "value {offset}"),
             }
         }
+
+        fn read(text: &str) -> Option<Self> {
+            match text.strip_prefix("value ") {
+                Some(offset) => offset.parse().ok().map(Event::Value),
+                None => read_common(text).map(Event::Common),
+            }
+        }
+    }
+}
+
+/// Full keyboard. Arrows move focus spatially and change values of active
+/// widgets, tab and page keys move focus in order.
+pub mod keyboard {
+    use crate::{event::CommonEvent, focus::Direction};
+    use embedded_graphics_simulator::{sdl2::Keycode, SimulatorEvent};
+
+    pub use super::dpad::Event;
+    use super::{is_debug_toggle, KeyChange, KeyMap};
+
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    pub enum Input {
+        Up,
+        Down,
+        Left,
+        Right,
+        Next,
+        Previous,
+        Enter,
+        Back,
+    }
+
+    impl Default for KeyMap<Input> {
+        fn default() -> Self {
+            KeyMap::new()
+                .bind(Keycode::Up, Input::Up)
+                .bind(Keycode::Down, Input::Down)
+                .bind(Keycode::Left, Input::Left)
+                .bind(Keycode::Right, Input::Right)
+                .bind(Keycode::Tab, Input::Next)
+                .bind(Keycode::PageDown, Input::Next)
+                .bind(Keycode::PageUp, Input::Previous)
+                .bind(Keycode::Return, Input::Enter)
+                .bind(Keycode::Return2, Input::Enter)
+                .bind(Keycode::KpEnter, Input::Enter)
+                .bind(Keycode::Space, Input::Enter)
+                .bind(Keycode::Backspace, Input::Back)
+        }
+    }
+
+    impl KeyMap<Input> {
+        /// Held keys except enter and back repeat
+        pub fn event(&self, event: SimulatorEvent) -> Option<Event> {
+            if let SimulatorEvent::Quit = event {
+                return Some(Event(CommonEvent::Exit));
+            }
+            if is_debug_toggle(&event) {
+                return Some(Event(CommonEvent::ToggleDebug));
+            }
+
+            let common = match self.key(&event)? {
+                (Input::Enter, KeyChange::Down) => CommonEvent::FocusButtonDown,
+                (Input::Enter, KeyChange::Up) => CommonEvent::FocusButtonUp,
+                (Input::Back, KeyChange::Down) => CommonEvent::Back,
+                (_, KeyChange::Up) | (Input::Enter | Input::Back, _) => return None,
+                (Input::Up, _) => CommonEvent::FocusDirection(Direction::Up),
+                (Input::Down, _) => CommonEvent::FocusDirection(Direction::Down),
+                (Input::Left, _) => CommonEvent::FocusDirection(Direction::Left),
+                (Input::Right, _) => CommonEvent::FocusDirection(Direction::Right),
+                (Input::Next, _) => CommonEvent::FocusMove(1),
+                (Input::Previous, _) => CommonEvent::FocusMove(-1),
+            };
+
+            Some(Event(common))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+    use embedded_graphics::geometry::Point;
+    use embedded_graphics_simulator::{
+        sdl2::{Keycode, Mod, MouseButton},
+        SimulatorEvent,
+    };
+
+    use crate::{event::CommonEvent, event::Event as _, focus::Direction, record::Record};
+
+    use super::{dpad, dual_encoder, keyboard, single_encoder, touch, KeyMap};
+
+    fn down(keycode: Keycode) -> SimulatorEvent {
+        SimulatorEvent::KeyDown { keycode, keymod: Mod::NOMOD, repeat: false }
+    }
+
+    fn held(keycode: Keycode) -> SimulatorEvent {
+        SimulatorEvent::KeyDown { keycode, keymod: Mod::NOMOD, repeat: true }
+    }
+
+    fn up(keycode: Keycode) -> SimulatorEvent {
+        SimulatorEvent::KeyUp { keycode, keymod: Mod::NOMOD, repeat: false }
+    }
+
+    /// Common events of a key press, hold and release
+    fn press<E: crate::event::Event>(
+        convert: impl Fn(SimulatorEvent) -> Option<E>,
+        keycode: Keycode,
+    ) -> Vec<Option<CommonEvent>> {
+        [down(keycode), held(keycode), up(keycode)]
+            .into_iter()
+            .filter_map(convert)
+            .map(|event| event.as_common())
+            .collect()
+    }
+
+    #[test]
+    fn binds_and_unbinds_keys() {
+        let map = KeyMap::new().bind(Keycode::A, 1).bind(Keycode::B, 1).bind(Keycode::A, 2);
+        assert_eq!(map.input(Keycode::A), Some(2));
+        assert_eq!(map.input(Keycode::B), Some(1));
+
+        let map = map.unbind(Keycode::A);
+        assert_eq!(map.input(Keycode::A), None);
+    }
+
+    #[test]
+    fn converts_through_custom_map() {
+        let keys = KeyMap::default().bind(Keycode::W, single_encoder::Input::RotateRight);
+        assert_eq!(press(|event| keys.event(event), Keycode::W), [Some(CommonEvent::FocusMove(1))]);
+        assert_eq!(press(|event| keys.event(event), Keycode::Q), []);
+
+        let keys = keys.unbind(Keycode::Space);
+        assert_eq!(press(|event| keys.event(event), Keycode::Space), []);
+    }
+
+    #[test]
+    fn single_encoder_rotates_on_release() {
+        let keys = KeyMap::<single_encoder::Input>::default();
+        let press = |keycode| press(|event| keys.event(event), keycode);
+
+        assert_eq!(press(Keycode::Left), [Some(CommonEvent::FocusMove(-1))]);
+        assert_eq!(
+            press(Keycode::Return),
+            [Some(CommonEvent::FocusButtonDown), Some(CommonEvent::FocusButtonUp)]
+        );
+        assert_eq!(press(Keycode::Escape), [Some(CommonEvent::Back)]);
+    }
+
+    #[test]
+    fn single_encoder_drops_recorded_pointer_moves() {
+        let read = |text| single_encoder::Event::read(text).and_then(|event| event.as_common());
+        assert_eq!(read("pointer_move 3 4"), None);
+        assert_eq!(read("pointer_down 3 4"), Some(CommonEvent::FocusButtonDown));
+    }
+
+    #[test]
+    fn touch_profile_has_pointer_and_keys() {
+        let keys = KeyMap::<touch::Input>::default();
+        let point = Point::new(3, 4);
+
+        let pointer = [
+            SimulatorEvent::MouseButtonDown { mouse_btn: MouseButton::Left, point },
+            SimulatorEvent::MouseMove { point },
+            SimulatorEvent::MouseButtonUp { mouse_btn: MouseButton::Left, point },
+        ]
+        .map(|event| keys.event(event).map(|event| event.0));
+        assert_eq!(
+            pointer,
+            [
+                Some(CommonEvent::PointerDown(point)),
+                Some(CommonEvent::PointerMove(point)),
+                Some(CommonEvent::PointerUp(point))
+            ]
+        );
+
+        let press = |keycode| press(|event| keys.event(event), keycode);
+        assert_eq!(press(Keycode::Down), [Some(CommonEvent::FocusMove(1))]);
+        assert_eq!(press(Keycode::Backspace), [Some(CommonEvent::Back)]);
+    }
+
+    #[test]
+    fn dpad_repeats_directions() {
+        let keys = KeyMap::<dpad::Input>::default();
+        let press = |keycode| press(|event| keys.event(event), keycode);
+
+        let up = Some(CommonEvent::FocusDirection(Direction::Up));
+        assert_eq!(press(Keycode::Up), [up, up]);
+        assert_eq!(
+            press(Keycode::Return),
+            [Some(CommonEvent::FocusButtonDown), Some(CommonEvent::FocusButtonUp)]
+        );
+
+        // Four-button pad
+        let keys = keys.unbind(Keycode::Return).unbind(Keycode::Space);
+        assert_eq!(keys.event(down(Keycode::Return)).map(|event| event.0), None);
+    }
+
+    #[test]
+    fn dual_encoder_splits_navigation_and_value() {
+        let keys = KeyMap::<dual_encoder::Input>::default();
+
+        assert!(matches!(
+            keys.event(down(Keycode::Right)),
+            Some(dual_encoder::Event::Common(CommonEvent::FocusMove(1)))
+        ));
+        assert!(matches!(keys.event(held(Keycode::A)), Some(dual_encoder::Event::Value(-1))));
+        assert!(matches!(
+            keys.event(down(Keycode::S)),
+            Some(dual_encoder::Event::Common(CommonEvent::Back))
+        ));
+        assert!(keys.event(up(Keycode::D)).is_none());
+    }
+
+    #[test]
+    fn keyboard_moves_focus_in_order_and_spatially() {
+        let keys = KeyMap::<keyboard::Input>::default();
+        let press = |keycode| press(|event| keys.event(event), keycode);
+
+        let next = Some(CommonEvent::FocusMove(1));
+        assert_eq!(press(Keycode::Tab), [next, next]);
+
+        let left = Some(CommonEvent::FocusDirection(Direction::Left));
+        assert_eq!(press(Keycode::Left), [left, left]);
+        assert_eq!(press(Keycode::Backspace), [Some(CommonEvent::Back)]);
+    }
+
+    #[test]
+    fn debug_toggle_and_quit_in_every_profile() {
+        let toggle = Some(CommonEvent::ToggleDebug);
+        let exit = Some(CommonEvent::Exit);
+
+        let single = KeyMap::<single_encoder::Input>::default();
+        let touch = KeyMap::<touch::Input>::default();
+        let dpad = KeyMap::<dpad::Input>::default();
+        let dual = KeyMap::<dual_encoder::Input>::default();
+        let keyboard = KeyMap::<keyboard::Input>::default();
+
+        for event in [down(Keycode::F12), SimulatorEvent::Quit] {
+            let expected = if let SimulatorEvent::Quit = event { exit } else { toggle };
+            let converted = [
+                single.event(event).and_then(|event| event.as_common()),
+                touch.event(event).and_then(|event| event.as_common()),
+                dpad.event(event).and_then(|event| event.as_common()),
+                dual.event(event).and_then(|event| event.as_common()),
+                keyboard.event(event).and_then(|event| event.as_common()),
+            ];
+            assert_eq!(converted, [expected; 5]);
+        }
     }
 }
@@ -1,10 +1,13 @@
-use core::ops::{Add, Div, Mul, Sub};
+use core::{
+    fmt,
+    ops::{Add, Div, Mul, Sub},
+};
 
 use embedded_graphics::geometry::Point;
 
 use crate::{axis::Axis, padding::Padding};
 
-#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
 #[cfg_attr(feature = "defmt", derive(::defmt::Format))]
 pub enum Length {
     /// Fills all the remaining space
@@ -43,6 +46,17 @@ impl Length {
     }
 }
 
+impl fmt::Display for Length {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Length::Fill => f.write_str("fill"),
+            Length::Shrink => f.write_str("shrink"),
+            Length::Div(div) => write!(f, "div({div})"),
+            Length::Fixed(fixed) => write!(f, "{fixed}"),
+        }
+    }
+}
+
 impl From<u32> for Length {
     fn from(value: u32) -> Self {
         Self::Fixed(value)
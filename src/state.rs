@@ -2,52 +2,226 @@ use alloc::{boxed::Box, vec::Vec};
 use core::{
     any::{Any, TypeId},
     borrow::Borrow,
+    cell::UnsafeCell,
+    fmt,
+    marker::PhantomData,
+    mem::{align_of, size_of, MaybeUninit},
 };
 
-use crate::{event::Event, render::Renderer, widget::Widget};
+use crate::{
+    el::ElId, event::Event, log::logger::warning, render::Renderer, widget::Widget,
+    widgets::router::PageId,
+};
+
+/// State of a widget kept in the state tree between view rebuilds. Must be
+/// `Default` so it can be re-initialized if the tree got out of sync with
+/// widgets, and `Debug` to be shown by [`UI::dump_tree`](crate::ui::UI::dump_tree).
+pub trait WidgetState: Any + Default + fmt::Debug {}
+
+impl<T: Any + Default + fmt::Debug> WidgetState for T {}
+
+/// Bytes of state stored inline in [`StateNode`], bigger states are boxed.
+/// Fits states of all built-in widgets, including animated ones.
+pub const INLINE_SIZE: usize = 64;
+
+/// Type-erased [`WidgetState`]. States of up to [`INLINE_SIZE`] bytes with
+/// alignment of up to 8 are stored inline, so widgets don't allocate for
+/// state. Access is a `TypeId` comparison, no dynamic dispatch.
+///
+/// Widgets are trait objects, so the type of state in a node is known only
+/// at runtime, the comparison can't be avoided. Mismatch is recoverable,
+/// see [`State::downcast_mut`].
+pub struct AnyState {
+    type_id: TypeId,
+    data: Data,
+    /// Drops the inline state, no-op for boxed one
+    drop: unsafe fn(*mut u8),
+    debug: unsafe fn(*const u8, &mut fmt::Formatter<'_>) -> fmt::Result,
+    /// State may be neither `Send` nor `Sync`
+    _marker: PhantomData<*const ()>,
+}
+
+/// Inline bytes are in `UnsafeCell`, so states with interior mutability,
+/// e.g. `Cell`, can be mutated through a shared reference to them
+enum Data {
+    Inline(UnsafeCell<[MaybeUninit<u64>; INLINE_SIZE / 8]>),
+    Boxed(Box<dyn Any>),
+}
+
+/// # Safety
+///
+/// `data` points to a valid `T` which is not used after the call
+unsafe fn drop_inline<T>(data: *mut u8) {
+    // SAFETY: Ensured by the caller
+    unsafe { core::ptr::drop_in_place(data as *mut T) }
+}
+
+unsafe fn drop_none(_: *mut u8) {}
+
+/// # Safety
+///
+/// `data` points to a valid `T` for the duration of the call
+unsafe fn debug_state<T: fmt::Debug>(data: *const u8, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    // SAFETY: Ensured by the caller
+    unsafe { &*(data as *const T) }.fmt(f)
+}
+
+impl AnyState {
+    pub fn new<T: WidgetState>(state: T) -> Self {
+        let fits = size_of::<T>() <= INLINE_SIZE && align_of::<T>() <= align_of::<u64>();
+
+        let (data, drop): (_, unsafe fn(*mut u8)) = if fits {
+            let mut data = [MaybeUninit::uninit(); INLINE_SIZE / 8];
+            // SAFETY: `T` fits: its size is at most `INLINE_SIZE`, the size of
+            // the buffer, and its alignment is at most the one of `u64`
+            unsafe { (data.as_mut_ptr() as *mut T).write(state) };
+            (Data::Inline(UnsafeCell::new(data)), drop_inline::<T>)
+        } else {
+            (Data::Boxed(Box::new(state)), drop_none)
+        };
 
-// TODO: Not just any, but specific "State" trait with Default, etc.??? Is it
-// possible
+        Self {
+            type_id: TypeId::of::<T>(),
+            data,
+            drop,
+            debug: debug_state::<T>,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn is<T: 'static>(&self) -> bool {
+        self.type_id == TypeId::of::<T>()
+    }
+
+    /// State is stored inline, without allocation
+    pub fn is_inline(&self) -> bool {
+        matches!(self.data, Data::Inline(_))
+    }
+
+    /// Pointer to the state of type `type_id`
+    fn ptr(&self) -> *const u8 {
+        match &self.data {
+            Data::Inline(data) => data.get() as *const u8,
+            Data::Boxed(data) => data.as_ref() as *const dyn Any as *const u8,
+        }
+    }
+
+    fn ptr_mut(&mut self) -> *mut u8 {
+        match &mut self.data {
+            Data::Inline(data) => data.get_mut().as_mut_ptr() as *mut u8,
+            Data::Boxed(data) => data.as_mut() as *mut dyn Any as *mut u8,
+        }
+    }
+
+    pub fn downcast_ref<T: 'static>(&self) -> Option<&T> {
+        // SAFETY: Type is checked, so the pointer is to a valid `T`, borrowed
+        // for the lifetime of `&self`
+        self.is::<T>().then(|| unsafe { &*(self.ptr() as *const T) })
+    }
+
+    pub fn downcast_mut<T: 'static>(&mut self) -> Option<&mut T> {
+        match self.is::<T>() {
+            // SAFETY: Type is checked, so the pointer is to a valid `T`,
+            // borrowed uniquely for the lifetime of `&mut self`
+            true => Some(unsafe { &mut *(self.ptr_mut() as *mut T) }),
+            false => None,
+        }
+    }
+}
+
+impl Drop for AnyState {
+    fn drop(&mut self) {
+        if let Data::Inline(data) = &mut self.data {
+            // SAFETY: Inline data holds a valid state of the type `drop` was
+            // created for, and it is not used after being dropped. Boxed
+            // state is dropped with its box.
+            unsafe { (self.drop)(data.get_mut().as_mut_ptr() as *mut u8) }
+        }
+    }
+}
+
+impl fmt::Debug for AnyState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // SAFETY: `debug` was created for the type of the state `ptr` points to
+        unsafe { (self.debug)(self.ptr(), f) }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(::defmt::Format))]
+pub enum StateError {
+    /// Node has no state
+    Stateless,
+    /// Node state is of other type than requested
+    Mismatch,
+}
 
 pub enum State {
     None,
-    Some(Box<dyn Any>),
+    Some(AnyState),
 }
 
 impl State {
-    pub fn new<T: 'static>(state: T) -> Self {
-        Self::Some(Box::new(state))
+    /// States of up to [`INLINE_SIZE`] bytes do not allocate
+    pub fn new<T: WidgetState>(state: T) -> Self {
+        Self::Some(AnyState::new(state))
+    }
+
+    pub fn try_downcast_ref<T: 'static>(&self) -> Result<&T, StateError> {
+        match self {
+            State::None => Err(StateError::Stateless),
+            State::Some(state) => state.downcast_ref().ok_or(StateError::Mismatch),
+        }
     }
 
-    pub fn downcast_ref<T: 'static>(&self) -> &T {
+    pub fn try_downcast_mut<T: 'static>(&mut self) -> Result<&mut T, StateError> {
         match self {
-            State::None => panic!("Downcast of stateless state"),
-            State::Some(state) => state.downcast_ref().expect("Downcast state"),
+            State::None => Err(StateError::Stateless),
+            State::Some(state) => state.downcast_mut().ok_or(StateError::Mismatch),
         }
     }
 
-    pub fn downcast_mut<T: 'static>(&mut self) -> &mut T {
+    /// Get state of type `T`, re-initializing it with default one if state
+    /// is missing or is of other type
+    pub fn downcast_mut<T: WidgetState>(&mut self) -> &mut T {
+        if self.try_downcast_ref::<T>().is_err() {
+            warning!("Re-initialize missing or mismatching widget state");
+            *self = Self::new(T::default());
+        }
+
         match self {
-            State::None => panic!("Downcast of stateless state"),
-            State::Some(state) => state.downcast_mut().expect("Downcast mut state"),
+            State::Some(state) => state.downcast_mut().unwrap(),
+            State::None => unreachable!(),
         }
     }
 
-    pub fn reset<T: 'static>(&mut self)
-    where
-        T: Default,
-    {
+    pub fn reset<T: WidgetState>(&mut self) {
+        *self.downcast_mut::<T>() = T::default();
+    }
+}
+
+impl fmt::Debug for State {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            State::None => panic!("Reset of stateless state"),
-            State::Some(state) => {
-                *state.downcast_mut::<T>().expect("Downcast reset state") = T::default();
-            },
+            State::None => f.write_str("None"),
+            State::Some(state) => state.fmt(f),
         }
     }
 }
 
-#[derive(Clone, Copy, PartialEq)]
-pub struct StateTag(pub TypeId);
+/// Type of widget state, nodes keep their states on rebuild only if tags
+/// match
+#[derive(Clone, Copy)]
+pub struct StateTag {
+    type_id: TypeId,
+    name: &'static str,
+}
+
+impl PartialEq for StateTag {
+    fn eq(&self, other: &Self) -> bool {
+        self.type_id == other.type_id
+    }
+}
 
 impl StateTag {
     pub fn stateless() -> Self {
@@ -55,19 +229,45 @@ impl StateTag {
     }
 
     pub fn of<T: 'static>() -> Self {
-        Self(TypeId::of::<T>())
+        Self { type_id: TypeId::of::<T>(), name: core::any::type_name::<T>() }
+    }
+
+    pub fn type_id(&self) -> TypeId {
+        self.type_id
+    }
+
+    /// Name of the state type from [`core::any::type_name`], for debugging
+    /// only
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    pub fn is_stateless(&self) -> bool {
+        *self == Self::stateless()
     }
 }
 
+/// Key of a [`StateNode`] among its siblings. Pages have their own key
+/// space, so a page named like an element id doesn't take its state.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StateKey {
+    El(ElId),
+    Page(PageId),
+}
+
 pub struct StateNode {
     pub tag: StateTag,
+    /// Identifier of the element owning the state. Used to find the state of
+    /// a moved element when tree changes, so give elements of reordered lists
+    /// custom ids to keep their states.
+    pub key: Option<StateKey>,
     pub state: State,
     pub children: Vec<StateNode>,
 }
 
 impl StateNode {
     pub fn stateless() -> Self {
-        Self { tag: StateTag::stateless(), state: State::None, children: vec![] }
+        Self { tag: StateTag::stateless(), key: None, state: State::None, children: vec![] }
     }
 
     pub fn new<'a, Message, R: Renderer, E: Event, S>(
@@ -75,21 +275,197 @@ impl StateNode {
     ) -> Self {
         let widget = widget.borrow();
 
-        Self { tag: widget.state_tag(), state: widget.state(), children: widget.state_children() }
+        Self {
+            tag: widget.state_tag(),
+            key: widget.id().map(StateKey::El),
+            state: widget.state(),
+            children: widget.state_children(),
+        }
     }
 
-    pub fn get<T: 'static>(&self) -> &T {
-        self.state.downcast_ref()
+    /// Merge state tree built for the new widget tree into this one.
+    /// Nodes are matched by key first, then by position. States of matched
+    /// nodes with the same tag are kept, nodes of new widgets are taken from
+    /// `fresh` and states of removed widgets are dropped.
+    pub fn reconcile(&mut self, fresh: StateNode) {
+        if self.tag != fresh.tag {
+            *self = fresh;
+            return;
+        }
+
+        self.key = fresh.key;
+
+        let mut stale =
+            core::mem::take(&mut self.children).into_iter().map(Some).collect::<Vec<_>>();
+
+        self.children = fresh
+            .children
+            .into_iter()
+            .enumerate()
+            .map(|(index, fresh)| {
+                let keyed = fresh.key.and_then(|key| {
+                    stale.iter().position(|old| matches!(old, Some(old) if old.key == Some(key)))
+                });
+
+                match keyed.or(Some(index)).and_then(|index| stale.get_mut(index)?.take()) {
+                    Some(mut old) => {
+                        old.reconcile(fresh);
+                        old
+                    },
+                    None => fresh,
+                }
+            })
+            .collect();
     }
 
-    pub fn get_mut<T: 'static>(&mut self) -> &mut T {
+    pub fn try_get<T: 'static>(&self) -> Result<&T, StateError> {
+        self.state.try_downcast_ref()
+    }
+
+    /// Get state of type `T`. Missing or mismatching state is replaced with
+    /// default one, see [`State::downcast_mut`].
+    pub fn get<T: WidgetState>(&mut self) -> &T {
+        self.get_mut()
+    }
+
+    pub fn get_mut<T: WidgetState>(&mut self) -> &mut T {
+        if self.state.try_downcast_ref::<T>().is_err() {
+            self.tag = StateTag::of::<T>();
+        }
         self.state.downcast_mut()
     }
 
-    pub fn reset<T: 'static>(&mut self)
-    where
-        T: Default,
-    {
+    pub fn reset<T: WidgetState>(&mut self) {
         self.state.reset::<T>()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use embedded_graphics::pixelcolor::Rgb888;
+
+    use crate::{
+        el::ElId, render::DrawTargetRenderer, testing::Event, theme::Theme, widget::Widget,
+        widgets::router::PageId,
+    };
+
+    use super::{
+        AnyState, State, StateError, StateKey, StateNode, StateTag, WidgetState, INLINE_SIZE,
+    };
+
+    fn node<T: WidgetState>(
+        key: Option<StateKey>,
+        state: T,
+        children: Vec<StateNode>,
+    ) -> StateNode {
+        StateNode { tag: StateTag::of::<T>(), key, state: State::new(state), children }
+    }
+
+    #[test]
+    fn keeps_matching_state() {
+        let mut old = node(None, 1u8, vec![node(None, 2u32, vec![])]);
+        old.reconcile(node(None, 0u8, vec![node(None, 0u32, vec![])]));
+
+        assert_eq!(*old.get::<u8>(), 1);
+        assert_eq!(*old.children[0].get::<u32>(), 2);
+    }
+
+    #[test]
+    fn replaces_state_of_other_kind() {
+        let mut old = node(None, 1u8, vec![node(None, 2u32, vec![])]);
+        old.reconcile(node(None, 0u8, vec![node(None, 0i32, vec![]), node(None, 0u32, vec![])]));
+
+        assert_eq!(old.children.len(), 2);
+        assert_eq!(*old.children[0].get::<i32>(), 0);
+        assert_eq!(*old.children[1].get::<u32>(), 0);
+    }
+
+    #[test]
+    fn follows_keys_on_reorder() {
+        let (a, b) = (Some(StateKey::El(ElId::new("a"))), Some(StateKey::El(ElId::new("b"))));
+
+        let mut old = node(None, (), vec![node(a, 1u8, vec![]), node(b, 2u8, vec![])]);
+        old.reconcile(node(None, (), vec![node(b, 0u8, vec![]), node(a, 0u8, vec![])]));
+
+        assert_eq!(*old.children[0].get::<u8>(), 2);
+        assert_eq!(*old.children[1].get::<u8>(), 1);
+    }
+
+    #[test]
+    fn keeps_pages_apart_from_elements() {
+        let element = Some(StateKey::El(ElId::new("a")));
+        let page = Some(StateKey::Page(PageId::new("a")));
+
+        let mut old = node(None, (), vec![node(element, 1u8, vec![]), node(page, 2u8, vec![])]);
+        old.reconcile(node(None, (), vec![node(page, 0u8, vec![]), node(element, 0u8, vec![])]));
+
+        assert_eq!(*old.children[0].get::<u8>(), 2);
+        assert_eq!(*old.children[1].get::<u8>(), 1);
+    }
+
+    #[test]
+    fn reinitializes_mismatching_state() {
+        let mut node = node(None, 1u8, vec![]);
+
+        assert_eq!(node.try_get::<u32>().err(), Some(StateError::Mismatch));
+        assert_eq!(*node.get::<u32>(), 0);
+        assert!(node.tag == StateTag::of::<u32>());
+    }
+
+    #[test]
+    fn reinitializes_missing_state() {
+        let mut node = StateNode::stateless();
+
+        assert_eq!(node.try_get::<u8>().err(), Some(StateError::Stateless));
+        *node.get_mut::<u8>() = 5;
+        assert_eq!(*node.get::<u8>(), 5);
+    }
+
+    #[test]
+    fn stores_small_states_inline() {
+        #[derive(Debug, Default)]
+        struct Big([u64; INLINE_SIZE / 8 + 1]);
+
+        let small = AnyState::new((1u32, true));
+        assert!(small.is_inline());
+        assert_eq!(small.downcast_ref::<(u32, bool)>(), Some(&(1, true)));
+        assert!(small.downcast_ref::<u32>().is_none());
+        assert_eq!(alloc::format!("{small:?}"), "(1, true)");
+
+        let button =
+            crate::helpers::button::<(), DrawTargetRenderer<Rgb888>, Event, Theme<Rgb888>>("OK");
+        assert!(matches!(Widget::state(&button), State::Some(state) if state.is_inline()));
+
+        let mut big = AnyState::new(Big::default());
+        assert!(!big.is_inline());
+        big.downcast_mut::<Big>().unwrap().0[INLINE_SIZE / 8] = 3;
+        assert_eq!(big.downcast_ref::<Big>().unwrap().0[INLINE_SIZE / 8], 3);
+    }
+
+    #[test]
+    fn mutates_inline_state_through_shared_reference() {
+        let state = AnyState::new(core::cell::Cell::new(1u8));
+        assert!(state.is_inline());
+
+        let cell = state.downcast_ref::<core::cell::Cell<u8>>().unwrap();
+        cell.set(2);
+        assert_eq!(state.downcast_ref::<core::cell::Cell<u8>>().unwrap().get(), 2);
+    }
+
+    #[test]
+    fn drops_inline_state() {
+        let counter = alloc::rc::Rc::new(());
+
+        let mut state = State::new(Some(counter.clone()));
+        assert_eq!(alloc::rc::Rc::strong_count(&counter), 2);
+
+        state.reset::<Option<alloc::rc::Rc<()>>>();
+        assert_eq!(alloc::rc::Rc::strong_count(&counter), 1);
+
+        *state.downcast_mut::<Option<alloc::rc::Rc<()>>>() = Some(counter.clone());
+        drop(state);
+        assert_eq!(alloc::rc::Rc::strong_count(&counter), 1);
+    }
+}
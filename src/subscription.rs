@@ -0,0 +1,259 @@
+use core::{
+    hash::{Hash, Hasher},
+    time::Duration,
+};
+
+use crate::{collections::Subscriptions, time::Instant};
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(::defmt::Format))]
+enum TimerKind {
+    /// Fire once after the duration
+    Timeout,
+    /// Fire every period
+    Interval,
+    /// Fire once after no events came during the duration
+    Idle,
+}
+
+/// Timer publishing a message on the UI clock. Subscriptions are declared
+/// all at once, see [`UI::subscribe`](crate::ui::UI::subscribe), and are
+/// identified by `id`: a subscription declared again with the same id and
+/// settings keeps its timer running, otherwise the timer restarts.
+///
+/// Timers are checked on each `UI::tick` and never fire if UI has no clock.
+pub struct Subscription<Message> {
+    id: &'static str,
+    kind: TimerKind,
+    duration: Duration,
+    /// Hash of debounced input, changing it restarts the timer
+    revision: u64,
+    message: Message,
+    clone: fn(&Message) -> Message,
+}
+
+impl<Message: Clone> Subscription<Message> {
+    fn new(id: &'static str, kind: TimerKind, duration: Duration, message: Message) -> Self {
+        Self { id, kind, duration, revision: 0, message, clone: Message::clone }
+    }
+
+    /// Publish `message` once, `delay` after the subscription first appeared
+    pub fn timeout(id: &'static str, delay: Duration, message: Message) -> Self {
+        Self::new(id, TimerKind::Timeout, delay, message)
+    }
+
+    /// Publish `message` every `period`. Missed periods are not caught up,
+    /// if the UI ticks rarely, message is published once per tick.
+    pub fn interval(id: &'static str, period: Duration, message: Message) -> Self {
+        Self::new(id, TimerKind::Interval, period, message)
+    }
+
+    /// Publish `message` once after `input` stopped changing for `delay`
+    pub fn debounce(id: &'static str, delay: Duration, input: impl Hash, message: Message) -> Self {
+        let mut hasher = FnvHasher::default();
+        input.hash(&mut hasher);
+
+        Self { revision: hasher.finish(), ..Self::timeout(id, delay, message) }
+    }
+
+    /// Publish `message` once after no input events came for `delay`, e.g.
+    /// to go back to the home screen
+    pub fn idle(id: &'static str, delay: Duration, message: Message) -> Self {
+        Self::new(id, TimerKind::Idle, delay, message)
+    }
+}
+
+impl<Message> Subscription<Message> {
+    pub fn id(&self) -> &'static str {
+        self.id
+    }
+
+    fn same_timer(&self, other: &Self) -> bool {
+        self.id == other.id
+            && self.kind == other.kind
+            && self.duration == other.duration
+            && self.revision == other.revision
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum TimerState {
+    /// Starts on the next poll, when the current time is known
+    Pending,
+    Scheduled(Instant),
+    Done,
+}
+
+struct Timer<Message> {
+    subscription: Subscription<Message>,
+    state: TimerState,
+}
+
+/// Running timers of [`Subscription`]s
+pub struct Timers<Message> {
+    timers: Subscriptions<Timer<Message>>,
+}
+
+impl<Message> Default for Timers<Message> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<Message> Timers<Message> {
+    pub fn new() -> Self {
+        Self { timers: Subscriptions::new() }
+    }
+
+    /// Replace subscriptions, keeping timers of ones declared again with
+    /// the same settings
+    pub fn subscribe(&mut self, subscriptions: impl IntoIterator<Item = Subscription<Message>>) {
+        let mut old = core::mem::take(&mut self.timers);
+
+        for subscription in subscriptions {
+            let state = old
+                .iter()
+                .position(|timer| timer.subscription.same_timer(&subscription))
+                .map(|index| old.swap_remove(index).state)
+                .unwrap_or(TimerState::Pending);
+
+            crate::collections::push_subscription(&mut self.timers, Timer { subscription, state });
+        }
+    }
+
+    /// Restart idle timers, called when an input event comes
+    pub fn reset_idle(&mut self) {
+        self.timers
+            .iter_mut()
+            .filter(|timer| timer.subscription.kind == TimerKind::Idle)
+            .for_each(|timer| timer.state = TimerState::Pending);
+    }
+
+    /// Start pending timers and publish messages of timers due at `now`
+    pub fn poll(&mut self, now: Instant, mut publish: impl FnMut(Message)) {
+        for timer in self.timers.iter_mut() {
+            let Subscription { kind, duration, ref message, clone, .. } = timer.subscription;
+
+            match timer.state {
+                TimerState::Pending => timer.state = TimerState::Scheduled(now + duration),
+                TimerState::Scheduled(deadline) if deadline <= now => {
+                    publish(clone(message));
+
+                    timer.state = match kind {
+                        TimerKind::Interval => {
+                            let next = deadline + duration;
+                            TimerState::Scheduled(if next <= now { now + duration } else { next })
+                        },
+                        TimerKind::Timeout | TimerKind::Idle => TimerState::Done,
+                    };
+                },
+                TimerState::Scheduled(_) | TimerState::Done => {},
+            }
+        }
+    }
+
+    /// The earliest time some timer fires at, firmware can sleep until it
+    /// if no input comes. Pending timers start on the next tick, so it is
+    /// `Instant::ZERO` if there are any.
+    pub fn next_deadline(&self) -> Option<Instant> {
+        self.timers
+            .iter()
+            .filter_map(|timer| match timer.state {
+                TimerState::Pending => Some(Instant::ZERO),
+                TimerState::Scheduled(deadline) => Some(deadline),
+                TimerState::Done => None,
+            })
+            .min()
+    }
+}
+
+/// FNV-1a, hashes debounced input without std
+struct FnvHasher(u64);
+
+impl Default for FnvHasher {
+    fn default() -> Self {
+        Self(0xcbf29ce484222325)
+    }
+}
+
+impl Hasher for FnvHasher {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= *byte as u64;
+            self.0 = self.0.wrapping_mul(0x100000001b3);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+    use core::time::Duration;
+
+    use crate::time::Instant;
+
+    use super::{Subscription, Timers};
+
+    fn poll(timers: &mut Timers<u8>, at: u64) -> Vec<u8> {
+        let mut published = Vec::new();
+        timers.poll(Instant::from_millis(at), |message| published.push(message));
+        published
+    }
+
+    #[test]
+    fn timeout_fires_once() {
+        let mut timers = Timers::new();
+        timers.subscribe([Subscription::timeout("toast", Duration::from_millis(100), 1)]);
+
+        assert!(poll(&mut timers, 0).is_empty());
+        assert!(poll(&mut timers, 99).is_empty());
+        assert_eq!(poll(&mut timers, 100), [1]);
+        assert!(poll(&mut timers, 300).is_empty());
+        assert_eq!(timers.next_deadline(), None);
+    }
+
+    #[test]
+    fn interval_keeps_running_on_resubscribe() {
+        let mut timers = Timers::new();
+        let clock = || Subscription::interval("clock", Duration::from_millis(100), 1);
+
+        timers.subscribe([clock()]);
+        poll(&mut timers, 0);
+        assert_eq!(poll(&mut timers, 100), [1]);
+
+        timers.subscribe([clock()]);
+        assert_eq!(timers.next_deadline(), Some(Instant::from_millis(200)));
+        // Missed periods are skipped
+        assert_eq!(poll(&mut timers, 450), [1]);
+        assert_eq!(timers.next_deadline(), Some(Instant::from_millis(550)));
+    }
+
+    #[test]
+    fn debounce_restarts_on_input_change() {
+        let mut timers = Timers::new();
+        let search = |input| Subscription::debounce("search", Duration::from_millis(100), input, 1);
+
+        timers.subscribe([search("a")]);
+        poll(&mut timers, 0);
+        timers.subscribe([search("ab")]);
+        poll(&mut timers, 50);
+        assert!(poll(&mut timers, 100).is_empty());
+        assert_eq!(poll(&mut timers, 150), [1]);
+    }
+
+    #[test]
+    fn idle_restarts_on_events() {
+        let mut timers = Timers::new();
+        timers.subscribe([Subscription::idle("home", Duration::from_millis(100), 1)]);
+
+        poll(&mut timers, 0);
+        timers.reset_idle();
+        poll(&mut timers, 80);
+        assert!(poll(&mut timers, 100).is_empty());
+        assert_eq!(poll(&mut timers, 180), [1]);
+    }
+}
@@ -0,0 +1,547 @@
+//! Headless rendering harness. [`Harness`] runs [`UI`] drawing into an
+//! in-memory [`Framebuffer`], so widgets can be tested with scripted events
+//! and compared to golden images without a display or a simulator window.
+//!
+//! Golden images are binary PPM files in `tests/golden` of the package being
+//! tested. Set `UPDATE_GOLDEN` environment variable to create missing ones
+//! or to overwrite the existing ones after an intended change, without it a
+//! missing golden image fails the test. On mismatch the actual frame is
+//! saved next to the golden one as `<name>.actual.ppm` and the differing
+//! pixels are printed.
+
+use core::{convert::Infallible, time::Duration};
+
+use alloc::{format, rc::Rc, string::String, vec::Vec};
+use embedded_graphics::{
+    draw_target::DrawTarget,
+    geometry::{OriginDimensions, Point, Size},
+    pixelcolor::{PixelColor, Rgb888, RgbColor},
+    primitives::Rectangle,
+    Pixel,
+};
+
+use crate::{
+    color::UiColor,
+    el::El,
+    event::{CommonEvent, KnobEvent, ScrollEvent, SelectEvent, SliderEvent},
+    palette::PaletteColor,
+    record::{Log, Record},
+    render::DrawTargetRenderer,
+    style::Styler,
+    theme::Theme,
+    time::{Clock, Instant, ManualClock},
+    ui::UI,
+    widget::Widget,
+};
+
+/// Plain framebuffer of `C` pixels
+#[derive(Clone, PartialEq)]
+pub struct Framebuffer<C: PixelColor> {
+    size: Size,
+    pixels: Vec<C>,
+}
+
+impl<C: PixelColor> Framebuffer<C> {
+    pub fn new(size: Size, background: C) -> Self {
+        Self { size, pixels: vec![background; (size.width * size.height) as usize] }
+    }
+
+    fn index(&self, point: Point) -> Option<usize> {
+        let (x, y) = (u32::try_from(point.x).ok()?, u32::try_from(point.y).ok()?);
+        (x < self.size.width && y < self.size.height).then_some((y * self.size.width + x) as usize)
+    }
+
+    pub fn pixel(&self, point: Point) -> Option<C> {
+        self.index(point).map(|index| self.pixels[index])
+    }
+
+    /// Pixels in rows from the top left corner
+    pub fn pixels(&self) -> &[C] {
+        &self.pixels
+    }
+}
+
+impl<C: PixelColor + Into<Rgb888>> Framebuffer<C> {
+    /// Binary PPM (P6) image
+    pub fn to_ppm(&self) -> Vec<u8> {
+        let mut ppm = format!("P6\n{} {}\n255\n", self.size.width, self.size.height).into_bytes();
+
+        for &pixel in &self.pixels {
+            let rgb: Rgb888 = pixel.into();
+            ppm.extend([rgb.r(), rgb.g(), rgb.b()]);
+        }
+
+        ppm
+    }
+}
+
+impl Framebuffer<Rgb888> {
+    /// Parse binary PPM image with 8-bit channels
+    pub fn from_ppm(ppm: &[u8]) -> Option<Self> {
+        let mut rest = ppm;
+        let mut header = [0u32; 3];
+
+        if rest.get(..2)? != b"P6" {
+            return None;
+        }
+        rest = &rest[2..];
+
+        for value in &mut header {
+            let start = rest.iter().position(|byte| !byte.is_ascii_whitespace())?;
+            let len = rest[start..].iter().position(|byte| !byte.is_ascii_digit())?;
+            *value = core::str::from_utf8(&rest[start..start + len]).ok()?.parse().ok()?;
+            rest = &rest[start + len..];
+        }
+
+        let [width, height, 255] = header else {
+            return None;
+        };
+
+        // Single whitespace separates the header from the data
+        let data = rest.get(1..)?;
+        if data.len() != (width * height * 3) as usize {
+            return None;
+        }
+
+        let pixels = data.chunks_exact(3).map(|rgb| Rgb888::new(rgb[0], rgb[1], rgb[2])).collect();
+
+        Some(Self { size: Size::new(width, height), pixels })
+    }
+}
+
+impl<C: PixelColor> OriginDimensions for Framebuffer<C> {
+    fn size(&self) -> Size {
+        self.size
+    }
+}
+
+impl<C: PixelColor> DrawTarget for Framebuffer<C> {
+    type Color = C;
+    type Error = Infallible;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        for Pixel(point, color) in pixels {
+            if let Some(index) = self.index(point) {
+                self.pixels[index] = color;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Event with only common events, value of active widgets is changed by
+/// [`CommonEvent::FocusMove`]
+#[derive(Clone, Copy, Debug)]
+pub struct Event(pub CommonEvent);
+
+impl From<CommonEvent> for Event {
+    fn from(value: CommonEvent) -> Self {
+        Self(value)
+    }
+}
+
+impl crate::event::Event for Event {
+    fn as_common(&self) -> Option<CommonEvent> {
+        Some(self.0)
+    }
+}
+
+impl SelectEvent for Event {}
+impl SliderEvent for Event {}
+impl KnobEvent for Event {}
+impl ScrollEvent for Event {}
+impl Record for Event {}
+
+/// Focus button click
+pub fn click() -> [Event; 2] {
+    [CommonEvent::FocusButtonDown.into(), CommonEvent::FocusButtonUp.into()]
+}
+
+/// Pointer press and release at `point`
+pub fn tap(point: Point) -> [Event; 2] {
+    [CommonEvent::PointerDown(point).into(), CommonEvent::PointerUp(point).into()]
+}
+
+/// Element of test views with [`Event`] and the default theme
+pub type View<Message = ()> =
+    El<'static, Message, DrawTargetRenderer<Rgb888>, Event, Theme<Rgb888>>;
+
+/// [`Harness`] running a [`View`]
+pub type Script<Message = ()> = Harness<'static, Message, Rgb888, Event, Theme<Rgb888>>;
+
+/// Screen size of [`harness`]
+pub const SCREEN: Size = Size::new(64, 32);
+
+/// [`Harness`] running `view` on a [`SCREEN`]-sized display
+pub fn harness<Message>(view: impl Into<View<Message>>) -> Script<Message> {
+    Harness::new(view.into(), SCREEN)
+}
+
+/// [`UI`] drawing into [`Framebuffer`] after each step, with a manual clock
+/// starting at zero
+pub struct Harness<'a, Message, C: UiColor, E: crate::event::Event, S: Styler<C>> {
+    ui: UI<'a, Message, DrawTargetRenderer<C>, E, S>,
+    clock: Rc<ManualClock>,
+    frame: Framebuffer<C>,
+}
+
+impl<'a, Message, C, E, S> Harness<'a, Message, C, E, S>
+where
+    C: UiColor,
+    E: crate::event::Event,
+    S: Styler<C>,
+{
+    pub fn new(root: impl Widget<Message, DrawTargetRenderer<C>, E, S> + 'a, size: Size) -> Self {
+        let clock = Rc::new(ManualClock::new(Instant::ZERO));
+
+        Self {
+            ui: UI::new(root, size.into()).clock(clock.clone()),
+            clock,
+            frame: Framebuffer::new(size, C::default()),
+        }
+        .draw()
+    }
+
+    fn draw(mut self) -> Self {
+        self.ui.draw(&mut self.frame);
+        self
+    }
+
+    pub fn auto_focus(mut self) -> Self {
+        self.ui.auto_focus();
+        self.draw()
+    }
+
+    /// Tick UI with `events` and draw it
+    pub fn events(mut self, events: impl IntoIterator<Item = E>) -> Self {
+        self.ui.tick(events.into_iter());
+        self.draw()
+    }
+
+    /// Move the clock, tick UI without events and draw it, e.g. to finish
+    /// animations or recognize a long press
+    pub fn advance(self, by: Duration) -> Self {
+        self.clock.advance(by);
+        self.events([])
+    }
+
+    /// Tick UI with events of `log` at their time since now, drawing it
+    /// after each tick
+    pub fn replay(mut self, log: &Log<E>) -> Self {
+        let start = self.clock.now();
+        let mut entries = log.entries();
+
+        while let Some(&(at, _)) = entries.first() {
+            let count = entries.iter().take_while(|&&(same, _)| same == at).count();
+            let (now, rest) = entries.split_at(count);
+            entries = rest;
+
+            self.clock.set(start + at);
+            self = self.events(now.iter().map(|(_, event)| event.clone()));
+        }
+
+        self
+    }
+
+    pub fn ui(&mut self) -> &mut UI<'a, Message, DrawTargetRenderer<C>, E, S> {
+        &mut self.ui
+    }
+
+    /// Messages published since the last call
+    pub fn messages(&mut self) -> Vec<Message> {
+        core::iter::from_fn(|| self.ui.deque_message()).collect()
+    }
+
+    pub fn frame(&self) -> &Framebuffer<C> {
+        &self.frame
+    }
+}
+
+impl<'a, Message, C, E> Harness<'a, Message, C, E, Theme<C>>
+where
+    C: PaletteColor + 'static,
+    E: crate::event::Event,
+{
+    /// Draw everything again with `theme`
+    pub fn theme(mut self, theme: Theme<C>) -> Self {
+        self.ui = self.ui.theme(theme);
+        self.ui.redraw();
+        self.draw()
+    }
+}
+
+/// All built-in themes with their names
+pub fn themes<C: UiColor>() -> [(&'static str, Theme<C>); 3] {
+    [("light", Theme::Light), ("dark", Theme::Dark), ("ayu_light", Theme::AyuLight)]
+}
+
+/// Bounds of differing pixels, their count and the first one
+fn compare(
+    expected: &Framebuffer<Rgb888>,
+    actual: &Framebuffer<Rgb888>,
+) -> Option<(Rectangle, usize, Point)> {
+    let width = actual.size.width as i32;
+    let differing = expected
+        .pixels
+        .iter()
+        .zip(&actual.pixels)
+        .enumerate()
+        .filter(|(_, (expected, actual))| expected != actual)
+        .map(|(index, _)| Point::new(index as i32 % width, index as i32 / width));
+
+    let mut count = 0;
+    let (mut min, mut max, mut first) = (Point::new(i32::MAX, i32::MAX), Point::zero(), None);
+    for point in differing {
+        count += 1;
+        first.get_or_insert(point);
+        min = min.component_min(point);
+        max = max.component_max(point);
+    }
+
+    first.map(|first| (Rectangle::with_corners(min, max), count, first))
+}
+
+/// Differing pixels in `bounds` as text, `#` for differing pixels and `.` for
+/// equal ones
+fn diff_map(
+    expected: &Framebuffer<Rgb888>,
+    actual: &Framebuffer<Rgb888>,
+    bounds: Rectangle,
+) -> String {
+    let mut map = String::new();
+
+    for y in bounds.rows() {
+        for x in bounds.columns() {
+            let point = Point::new(x, y);
+            map.push(if expected.pixel(point) == actual.pixel(point) { '.' } else { '#' });
+        }
+        map.push('\n');
+    }
+
+    map
+}
+
+fn hex(color: Option<Rgb888>) -> String {
+    color.map_or_else(|| "none".into(), |c| format!("#{:02x}{:02x}{:02x}", c.r(), c.g(), c.b()))
+}
+
+/// Draw `view` after `script` in each of [`themes`] and compare frames to
+/// golden images `<name>_<theme>`
+pub fn snapshot(name: &str, view: impl Fn() -> View, script: impl Fn(Script) -> Script) {
+    for (theme_name, theme) in themes() {
+        let harness = script(harness(view()).theme(theme));
+        assert_golden(harness.frame(), &format!("{name}_{theme_name}"));
+    }
+}
+
+/// Compare `frame` to golden image `name`, panics describing the difference
+/// if they don't match
+pub fn assert_golden<C: PixelColor + Into<Rgb888>>(frame: &Framebuffer<C>, name: &str) {
+    let dir = std::path::PathBuf::from(
+        std::env::var("CARGO_MANIFEST_DIR").expect("Golden images are compared under cargo"),
+    )
+    .join("tests")
+    .join("golden");
+    let path = dir.join(format!("{name}.ppm"));
+    let actual_path = dir.join(format!("{name}.actual.ppm"));
+    let ppm = frame.to_ppm();
+
+    if std::env::var_os("UPDATE_GOLDEN").is_some() {
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(&path, &ppm).unwrap();
+        std::fs::remove_file(&actual_path).ok();
+        return;
+    }
+
+    if !path.exists() {
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(&actual_path, &ppm).unwrap();
+        panic!(
+            "Golden image {} is missing, frame is saved as {}. Run tests with UPDATE_GOLDEN=1 \
+             to create it.",
+            path.display(),
+            actual_path.display()
+        );
+    }
+
+    let expected = std::fs::read(&path).unwrap();
+    let expected = Framebuffer::from_ppm(&expected)
+        .unwrap_or_else(|| panic!("Golden image {} is not a binary PPM", path.display()));
+    let actual = Framebuffer::from_ppm(&ppm).unwrap();
+
+    if expected.size != actual.size {
+        std::fs::write(&actual_path, &ppm).unwrap();
+        panic!(
+            "Frame {name} is {}x{}, but golden image is {}x{}",
+            actual.size.width, actual.size.height, expected.size.width, expected.size.height
+        );
+    }
+
+    let Some((bounds, count, first)) = compare(&expected, &actual) else {
+        std::fs::remove_file(&actual_path).ok();
+        return;
+    };
+
+    std::fs::write(&actual_path, &ppm).unwrap();
+    panic!(
+        "Frame {name} differs from golden image in {count} pixels, saved as {}\n\
+         First at ({}, {}): expected {}, got {}\n\
+         Differing pixels in ({}, {})..({}, {}):\n{}",
+        actual_path.display(),
+        first.x,
+        first.y,
+        hex(expected.pixel(first)),
+        hex(actual.pixel(first)),
+        bounds.top_left.x,
+        bounds.top_left.y,
+        bounds.top_left.x + bounds.size.width as i32,
+        bounds.top_left.y + bounds.size.height as i32,
+        diff_map(&expected, &actual, bounds),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::string::String;
+    use embedded_graphics::{geometry::Size, pixelcolor::Rgb888};
+
+    use crate::{
+        col,
+        event::CommonEvent,
+        helpers::{
+            bar_h, bar_v, button, checkbox, container, h_div, knob, overlay, router, scrollable_v,
+            select_h, slider_h, text,
+        },
+        icons::IconKind,
+        row,
+        size::Length,
+        widgets::icon::Icon,
+    };
+
+    use super::{assert_golden, click, snapshot, Event, Framebuffer, Script};
+
+    /// Focus the first element and send `events` after clicking it
+    fn activate(
+        events: impl IntoIterator<Item = CommonEvent> + Clone,
+    ) -> impl Fn(Script) -> Script {
+        move |harness| {
+            harness.auto_focus().events(click()).events(events.clone().into_iter().map(Event::from))
+        }
+    }
+
+    #[test]
+    fn bar() {
+        snapshot("bar", || row![bar_h().value(0.3), bar_v().value(0.7)].gap(2).into(), |h| h);
+    }
+
+    #[test]
+    fn button_focused_and_pressed() {
+        snapshot("button", || col![button("OK"), button("Cancel")].into(), |h| h.auto_focus());
+        snapshot(
+            "button_pressed",
+            || button("OK").into(),
+            |h| h.auto_focus().events([CommonEvent::FocusButtonDown.into()]),
+        );
+    }
+
+    #[test]
+    fn checkbox_checked() {
+        snapshot("checkbox", || checkbox(|_| ()).into(), activate([]));
+    }
+
+    #[test]
+    fn container_and_divider() {
+        snapshot("container", || container("Box").into(), |h| h);
+        snapshot("divider", || col!["Top", h_div(), "Bottom"].into(), |h| h);
+    }
+
+    #[test]
+    fn icon() {
+        snapshot(
+            "icon",
+            || {
+                let icon = |kind| Icon::new(kind).size(16);
+                row![icon(IconKind::ArrowLeft), icon(IconKind::ArrowRight), icon(IconKind::Cross)]
+                    .into()
+            },
+            |h| h,
+        );
+    }
+
+    #[test]
+    fn knob_rotated() {
+        snapshot("knob", || knob(|_| ()).into(), activate([CommonEvent::FocusMove(64)]));
+    }
+
+    #[test]
+    fn linear() {
+        snapshot("linear", || row![col!["a", "b"], col!["c", "d"]].gap(4).padding(2).into(), |h| h);
+    }
+
+    #[test]
+    fn overlay_open() {
+        snapshot("overlay", || overlay(text("Base"), container("Top")).into(), |h| h);
+    }
+
+    #[test]
+    fn router_first_page() {
+        snapshot("router", || router().page("main", "Main").page("other", "Other").into(), |h| h);
+    }
+
+    #[test]
+    fn scrollable_scrolled() {
+        snapshot(
+            "scrollable",
+            || {
+                scrollable_v(
+                    col!["One", "Two", "Three", "Four", "Five", "Six"].height(Length::Shrink),
+                )
+                .into()
+            },
+            activate([CommonEvent::FocusMove(3)]),
+        );
+    }
+
+    #[test]
+    fn select_shifted() {
+        snapshot(
+            "select",
+            || select_h(["one", "two", "three"]).into(),
+            activate([CommonEvent::FocusMove(1)]),
+        );
+    }
+
+    #[test]
+    fn slider_shifted() {
+        snapshot("slider", || slider_h(|_| ()).into(), activate([CommonEvent::FocusMove(128)]));
+    }
+
+    #[test]
+    fn missing_golden_fails() {
+        // Would create the golden image
+        if std::env::var_os("UPDATE_GOLDEN").is_some() {
+            return;
+        }
+
+        let frame = Framebuffer::new(Size::new(2, 2), Rgb888::default());
+        let result =
+            std::panic::catch_unwind(|| assert_golden(&frame, "missing_golden_fails_test"));
+
+        let actual = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/golden/missing_golden_fails_test.actual.ppm");
+        assert!(actual.exists());
+        std::fs::remove_file(actual).unwrap();
+
+        let message = result.unwrap_err();
+        assert!(message.downcast_ref::<String>().unwrap().contains("is missing"));
+    }
+
+    #[test]
+    fn plain_text() {
+        snapshot("text", || "Hello".into(), |h| h);
+    }
+}
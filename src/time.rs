@@ -0,0 +1,113 @@
+use core::{
+    cell::Cell,
+    ops::{Add, Sub},
+    time::Duration,
+};
+
+/// Point in time, milliseconds passed since some start, e.g. device boot
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "defmt", derive(::defmt::Format))]
+pub struct Instant(u64);
+
+impl Instant {
+    pub const ZERO: Self = Self(0);
+
+    pub const fn from_millis(millis: u64) -> Self {
+        Self(millis)
+    }
+
+    pub const fn millis(self) -> u64 {
+        self.0
+    }
+
+    /// Time passed since `earlier`, zero if `earlier` is later than `self`
+    pub fn duration_since(self, earlier: Instant) -> Duration {
+        Duration::from_millis(self.0.saturating_sub(earlier.0))
+    }
+}
+
+impl Add<Duration> for Instant {
+    type Output = Self;
+
+    fn add(self, rhs: Duration) -> Self::Output {
+        Self(self.0.saturating_add(rhs.as_millis() as u64))
+    }
+}
+
+impl Sub for Instant {
+    type Output = Duration;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        self.duration_since(rhs)
+    }
+}
+
+/// Time source for the UI, e.g. a hardware timer
+pub trait Clock {
+    fn now(&self) -> Instant;
+}
+
+impl<F: Fn() -> Instant> Clock for F {
+    fn now(&self) -> Instant {
+        self()
+    }
+}
+
+impl<C: Clock + ?Sized> Clock for alloc::rc::Rc<C> {
+    fn now(&self) -> Instant {
+        self.as_ref().now()
+    }
+}
+
+/// Clock moved by hand, for tests and simulations
+#[derive(Default)]
+pub struct ManualClock {
+    now: Cell<Instant>,
+}
+
+impl ManualClock {
+    pub fn new(now: Instant) -> Self {
+        Self { now: Cell::new(now) }
+    }
+
+    pub fn set(&self, now: Instant) {
+        self.now.set(now)
+    }
+
+    pub fn advance(&self, by: Duration) {
+        self.now.set(self.now.get() + by)
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> Instant {
+        self.now.get()
+    }
+}
+
+/// Clock counting from its creation
+#[cfg(feature = "std")]
+pub struct StdClock {
+    start: std::time::Instant,
+}
+
+#[cfg(feature = "std")]
+impl StdClock {
+    pub fn new() -> Self {
+        Self { start: std::time::Instant::now() }
+    }
+}
+
+#[cfg(feature = "std")]
+impl Default for StdClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "std")]
+impl Clock for StdClock {
+    fn now(&self) -> Instant {
+        Instant::from_millis(self.start.elapsed().as_millis() as u64)
+    }
+}
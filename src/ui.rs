@@ -1,38 +1,93 @@
-use alloc::{boxed::Box, collections::VecDeque};
+use core::ops::ControlFlow;
+
+use alloc::{boxed::Box, vec::Vec};
 use embedded_graphics::{
     draw_target::DrawTarget,
+    geometry::Point,
     pixelcolor::{BinaryColor, Rgb555, Rgb565, Rgb666, Rgb888},
+    primitives::Rectangle,
 };
 
 use crate::{
+    action::{Action, WidgetAction},
+    animation::{Animated, Interpolate, Transition},
+    collections::MessageQueue,
     color::UiColor,
+    debug::{self, EventRoute, EventTrace, TreeDump},
     el::{El, ElId},
-    event::{Event, EventStub, Propagate},
+    event::{CommonEvent, Event, EventStub, Propagate},
+    focus::{self, FocusScope},
+    gesture::Gestures,
     layout::{Layout, LayoutNode, Limits, Viewport},
     palette::PaletteColor,
     render::{DrawTargetRenderer, Renderer},
     size::Size,
     state::StateNode,
     style::Styler,
+    subscription::{Subscription, Timers},
     theme::Theme,
+    time::{Clock, Instant},
     widget::Widget,
 };
 
 /// Global UI states collection
 pub struct UiCtx<Message> {
-    message_pool: VecDeque<Message>,
+    message_pool: MessageQueue<Message>,
     focused: Option<ElId>,
+    /// Focused element at the last draw, both need redraw on focus change
+    drawn_focus: Option<ElId>,
+    layout_invalid: bool,
+    /// Union of regions to redraw on the next draw
+    dirty: Option<Rectangle>,
+    /// Region being redrawn now
+    redrawing: Option<Rectangle>,
+    /// Time of the current tick or draw, `None` if UI has no clock
+    now: Option<Instant>,
+    /// Elements of an open modal overlay, focus can't leave them
+    focus_trap: Option<Vec<ElId>>,
+    /// Focus order of the whole tree, cached on layout
+    focus_order: Vec<ElId>,
+    /// What happens when focus reaches an end of the UI
+    root_scope: FocusScope,
+    /// Draw debug boxes above the UI
+    debug: bool,
+    /// Routes of events of the last tick with events, recorded in debug mode
+    event_trace: Vec<EventTrace>,
+    /// Innermost element with id which captured the event being dispatched
+    captured: Option<ElId>,
 }
 
 impl<Message> UiCtx<Message> {
     pub fn new() -> Self {
-        Self { message_pool: VecDeque::new(), focused: None }
+        Self {
+            message_pool: MessageQueue::new(),
+            focused: None,
+            drawn_focus: None,
+            layout_invalid: false,
+            dirty: None,
+            redrawing: None,
+            now: None,
+            focus_trap: None,
+            focus_order: Vec::new(),
+            root_scope: FocusScope::Trap,
+            debug: false,
+            event_trace: Vec::new(),
+            captured: None,
+        }
+    }
+
+    pub fn now(&self) -> Option<Instant> {
+        self.now
     }
 
     pub fn focus(&mut self, id: ElId) {
         self.focused = Some(id)
     }
 
+    pub fn focused(&self) -> Option<ElId> {
+        self.focused
+    }
+
     pub fn is_focused<R: Renderer, E: Event, S>(
         &self,
         widget: &impl Widget<Message, R, E, S>,
@@ -47,11 +102,113 @@ impl<Message> UiCtx<Message> {
         self.focused.is_none()
     }
 
+    /// Remember element which captured the event being dispatched, the
+    /// innermost one comes first
+    pub(crate) fn captured_by(&mut self, id: ElId) {
+        self.captured.get_or_insert(id);
+    }
+
+    /// Keep focus inside of `ids` and don't pass events to the main tree
+    /// until the next layout. Modal overlays call it on layout.
+    pub fn trap_focus(&mut self, ids: Vec<ElId>) {
+        self.focus_trap = Some(ids)
+    }
+
+    /// Move focus into the trap if it is outside, e.g. when a modal overlay
+    /// opens
+    fn enter_focus_trap(&mut self) {
+        if let Some(trap) = &self.focus_trap {
+            if !self.focused.is_some_and(|focused| trap.contains(&focused)) {
+                self.focused = trap.first().copied();
+            }
+        }
+    }
+
+    /// Is a modal overlay open
+    pub fn is_modal(&self) -> bool {
+        self.focus_trap.is_some()
+    }
+
+    /// Elements focus moves between, the trapped ones if a modal overlay is
+    /// open
+    fn focusable(&self) -> &[ElId] {
+        self.focus_trap.as_deref().unwrap_or(&self.focus_order)
+    }
+
+    /// Request relayout before the next draw. Widgets call it when their
+    /// intrinsic size changed.
+    pub fn invalidate_layout(&mut self) {
+        self.layout_invalid = true
+    }
+
+    /// Redraw region on the next draw. Widgets mark their bounds when their
+    /// state changes so they look different.
+    pub fn mark_dirty(&mut self, bounds: Rectangle) {
+        if bounds.is_zero_sized() {
+            return;
+        }
+
+        self.dirty = Some(union(self.dirty, bounds))
+    }
+
+    /// Does region intersect with the one being redrawn
+    pub fn is_dirty(&self, bounds: Rectangle) -> bool {
+        self.redrawing.is_some_and(|redrawing| !redrawing.intersection(&bounds).is_zero_sized())
+    }
+
+    /// Move animated value towards `target` and get its current value.
+    /// `bounds` are redrawn until the transition ends. Without clock values
+    /// change instantly.
+    pub fn animate<T: Interpolate>(
+        &mut self,
+        animated: &mut Option<Animated<T>>,
+        target: T,
+        transition: Transition,
+        bounds: Rectangle,
+    ) -> T {
+        let Some(now) = self.now else {
+            *animated = Some(Animated::new(target));
+            return target;
+        };
+
+        let animated = animated.get_or_insert_with(|| Animated::new(target));
+        animated.set(target, transition, now);
+
+        if animated.is_running(now) {
+            self.mark_dirty(bounds);
+        }
+
+        animated.value(now)
+    }
+
     pub fn publish(&mut self, message: Message) {
+        #[cfg(feature = "heapless")]
+        if self.message_pool.push_back(message).is_err() {
+            panic!("Too many messages, the limit is {}", crate::collections::MAX_MESSAGES);
+        }
+
+        #[cfg(not(feature = "heapless"))]
         self.message_pool.push_back(message)
     }
 }
 
+fn union(region: Option<Rectangle>, bounds: Rectangle) -> Rectangle {
+    match region {
+        Some(region) if !region.is_zero_sized() => Rectangle::with_corners(
+            region.top_left.component_min(bounds.top_left),
+            region.bottom_right().unwrap().component_max(bounds.bottom_right().unwrap()),
+        ),
+        _ => bounds,
+    }
+}
+
+fn earliest(a: Option<Instant>, b: Option<Instant>) -> Option<Instant> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(a.min(b)),
+        (a, None) | (None, a) => a,
+    }
+}
+
 pub struct UI<
     'a,
     Message,
@@ -67,6 +224,93 @@ pub struct UI<
     // events: Vec<E>,
     ctx: UiCtx<Message>,
     on_exit: Option<Box<dyn FnOnce()>>,
+    clock: Option<Box<dyn Clock>>,
+    timers: Timers<Message>,
+    gestures: Gestures,
+}
+
+/// Everything [`UI`] keeps between view rebuilds, so the root element can be
+/// dropped and rebuilt from the application state without losing focus,
+/// widget states and styler.
+pub struct UiCache<Message, S> {
+    viewport_size: Size,
+    root_node: LayoutNode,
+    root_state: StateNode,
+    styler: S,
+    ctx: UiCtx<Message>,
+    on_exit: Option<Box<dyn FnOnce()>>,
+    clock: Option<Box<dyn Clock>>,
+    timers: Timers<Message>,
+    gestures: Gestures,
+}
+
+impl<Message, S: Default> UiCache<Message, S> {
+    pub fn new(viewport_size: Size) -> Self {
+        Self {
+            viewport_size,
+            root_node: LayoutNode::default(),
+            root_state: StateNode::stateless(),
+            styler: Default::default(),
+            ctx: UiCtx::new(),
+
+            #[cfg(feature = "std")]
+            on_exit: Some(Box::new(|| std::process::exit(0))),
+
+            #[cfg(not(feature = "std"))]
+            on_exit: None,
+
+            clock: None,
+            timers: Timers::new(),
+            gestures: Gestures::new(),
+        }
+    }
+}
+
+impl<Message, S> UiCache<Message, S> {
+    pub fn styler(mut self, styler: S) -> Self {
+        self.styler = styler;
+        self
+    }
+
+    pub fn on_exit(mut self, f: impl FnOnce() + 'static) -> Self {
+        self.on_exit = Some(Box::new(f));
+        self
+    }
+
+    /// Time source for animations. Without it animated values change
+    /// instantly.
+    pub fn clock(mut self, clock: impl Clock + 'static) -> Self {
+        self.clock = Some(Box::new(clock));
+        self
+    }
+
+    /// See [`UI::wrap_focus`]
+    pub fn wrap_focus(mut self) -> Self {
+        self.ctx.root_scope = FocusScope::Wrap;
+        self
+    }
+
+    /// See [`UI::subscribe`]
+    pub fn subscribe(&mut self, subscriptions: impl IntoIterator<Item = Subscription<Message>>) {
+        self.timers.subscribe(subscriptions)
+    }
+
+    /// See [`UI::gestures`]
+    pub fn gestures(mut self, gestures: Gestures) -> Self {
+        self.gestures = gestures;
+        self
+    }
+
+    /// See [`UI::debug`]
+    pub fn debug(mut self, debug: bool) -> Self {
+        self.ctx.debug = debug;
+        self
+    }
+
+    /// See [`UI::next_deadline`]
+    pub fn next_deadline(&self) -> Option<Instant> {
+        earliest(self.timers.next_deadline(), self.gestures.next_deadline())
+    }
 }
 
 impl<'a, Message, C, E, S> UI<'a, Message, DrawTargetRenderer<C>, E, S>
@@ -75,28 +319,107 @@ where
     E: Event,
     S: Styler<C>,
 {
+    /// Draw regions marked dirty since the last draw, the rest of the
+    /// target is kept as is
     pub fn draw<D>(&mut self, target: &mut D)
     where
         D: DrawTarget<Color = C>,
         D::Error: core::fmt::Debug,
     {
-        // FIXME: Performance?
-        // TODO: Maybe should clear only root bounds
-        // renderer.clear(self.styler.background());
-        let mut renderer =
-            DrawTargetRenderer::new(target.bounding_box().size, self.styler.background());
+        self.update_time();
 
-        self.root.draw(
-            &mut self.ctx,
-            &mut self.root_state,
-            &mut renderer,
-            &self.styler,
-            Layout::new(&self.root_node),
-            &Viewport { size: self.viewport_size },
-        );
+        if self.ctx.layout_invalid {
+            self.relayout();
+        }
+
+        if self.ctx.focused != self.ctx.drawn_focus {
+            for id in [self.ctx.drawn_focus, self.ctx.focused].into_iter().flatten() {
+                self.perform(Action::redraw(id));
+            }
+            self.ctx.drawn_focus = self.ctx.focused;
+        }
+
+        let mut renderer = loop {
+            // Regions marked while drawing, e.g. by running animations, are
+            // redrawn on the next draw
+            if let Some(dirty) = self.ctx.dirty.take() {
+                self.ctx.redrawing = Some(union(self.ctx.redrawing, dirty));
+            }
 
+            let Some(redrawing) = self.ctx.redrawing else {
+                return;
+            };
+
+            let mut renderer = DrawTargetRenderer::region(
+                redrawing.intersection(&target.bounding_box()),
+                self.styler.background(),
+            );
+
+            self.root.draw(
+                &mut self.ctx,
+                &mut self.root_state,
+                &mut renderer,
+                &self.styler,
+                Layout::new(&self.root_node),
+                &Viewport { size: self.viewport_size },
+            );
+
+            self.root.draw_overlay(
+                &mut self.ctx,
+                &mut self.root_state,
+                &mut renderer,
+                &self.styler,
+                Layout::new(&self.root_node),
+                &Viewport { size: self.viewport_size },
+            );
+
+            // Widget found out its size changed while drawing, draw it once
+            // again not to show the stale layout
+            if self.ctx.layout_invalid {
+                self.relayout();
+                continue;
+            }
+
+            break renderer;
+        };
+
+        if self.ctx.debug {
+            self.draw_debug(&mut renderer);
+        }
+
+        self.ctx.redrawing = None;
         renderer.finish(target);
     }
+
+    /// Draw boxes of all widgets, then highlight the focused element and
+    /// elements events of the last tick went through
+    fn draw_debug(&mut self, renderer: &mut DrawTargetRenderer<C>) {
+        let layout = Layout::new(&self.root_node);
+
+        let viewport = Viewport { size: self.viewport_size };
+        if let Some(debug) = self.root.debug(&mut self.root_state, layout.clone(), &viewport) {
+            debug.draw(renderer);
+        }
+
+        let mut bounds = Vec::new();
+        self.root.focus_bounds(&mut self.root_state, layout, &mut bounds);
+        let bounds_of =
+            |id| bounds.iter().find(|&&(bounded, _)| bounded == id).map(|&(_, bounds)| bounds);
+
+        if let Some(focused) = self.ctx.focused.and_then(bounds_of) {
+            debug::draw_focus(renderer, focused);
+        }
+
+        for trace in &self.ctx.event_trace {
+            let (EventRoute::Captured(Some(id)) | EventRoute::BubbledUp(id)) = trace.route else {
+                continue;
+            };
+
+            if let Some(routed) = bounds_of(id) {
+                debug::draw_route(renderer, trace.route, routed);
+            }
+        }
+    }
 }
 
 impl<'a, Message, R: Renderer, E: Event, S: Styler<R::Color>> UI<'a, Message, R, E, S> {
@@ -114,7 +437,10 @@ impl<'a, Message, R: Renderer, E: Event, S: Styler<R::Color>> UI<'a, Message, R,
             &Viewport { size: viewport_size },
         );
 
-        Self {
+        ctx.focus_order = root.tree_ids();
+        ctx.enter_focus_trap();
+
+        let mut ui = Self {
             root,
             root_node,
             viewport_size,
@@ -124,10 +450,160 @@ impl<'a, Message, R: Renderer, E: Event, S: Styler<R::Color>> UI<'a, Message, R,
             ctx,
 
             #[cfg(feature = "std")]
-            on_exit: Box::new(|| std::process::exit(0)),
+            on_exit: Some(Box::new(|| std::process::exit(0))),
 
             #[cfg(not(feature = "std"))]
             on_exit: None,
+
+            clock: None,
+            timers: Timers::new(),
+            gestures: Gestures::new(),
+        };
+        ui.redraw();
+        ui
+    }
+
+    /// Build UI from new root reusing states kept from previous UI. See
+    /// [`StateNode::reconcile`] for which states are kept.
+    pub fn with_cache(
+        root: impl Into<El<'a, Message, R, E, S>>,
+        cache: UiCache<Message, S>,
+    ) -> Self {
+        let UiCache {
+            viewport_size,
+            root_node,
+            mut root_state,
+            styler,
+            ctx,
+            on_exit,
+            clock,
+            timers,
+            gestures,
+        } = cache;
+
+        let root = root.into();
+        root_state.reconcile(StateNode::new(&root));
+
+        let mut ui = Self {
+            root,
+            root_node,
+            viewport_size,
+            root_state,
+            styler,
+            ctx,
+            on_exit,
+            clock,
+            timers,
+            gestures,
+        };
+        ui.relayout();
+        ui
+    }
+
+    /// Replace the root element, keeping states of widgets which are still
+    /// in the tree
+    pub fn rebuild(&mut self, root: impl Into<El<'a, Message, R, E, S>>) {
+        self.root = root.into();
+        self.root_state.reconcile(StateNode::new(&self.root));
+        self.relayout();
+    }
+
+    /// Change viewport size, e.g. on display rotation, and relayout
+    pub fn resize(&mut self, viewport_size: Size) {
+        self.viewport_size = viewport_size;
+        self.relayout();
+    }
+
+    /// Relayout before the next draw
+    pub fn invalidate_layout(&mut self) {
+        self.ctx.invalidate_layout()
+    }
+
+    /// Draw bounds and content boxes of all widgets above the UI, highlight
+    /// the focused element and elements which captured or bubbled up events
+    /// of the last tick. [`CommonEvent::ToggleDebug`] toggles it at runtime.
+    pub fn debug(mut self, debug: bool) -> Self {
+        self.ctx.debug = debug;
+        self
+    }
+
+    pub fn toggle_debug(&mut self) {
+        self.ctx.debug = !self.ctx.debug;
+        self.ctx.event_trace.clear();
+        self.redraw();
+    }
+
+    pub fn is_debug(&self) -> bool {
+        self.ctx.debug
+    }
+
+    /// Routes of events of the last tick with events, recorded only in debug
+    /// mode
+    pub fn event_trace(&self) -> &[EventTrace] {
+        &self.ctx.event_trace
+    }
+
+    /// Widget tree with bounds, requested sizes and states, to print with
+    /// `Display`, e.g. `defmt::info!("{}", Display2Format(&ui.dump_tree()))`.
+    /// The format doesn't depend on element creation order, so dumps can be
+    /// compared in tests, see [`TreeDump`].
+    pub fn dump_tree(&mut self) -> TreeDump<R::Color> {
+        if self.ctx.layout_invalid {
+            self.relayout();
+        }
+
+        TreeDump {
+            root: self.root.debug(
+                &mut self.root_state,
+                Layout::new(&self.root_node),
+                &Viewport { size: self.viewport_size },
+            ),
+        }
+    }
+
+    /// Redraw the whole UI on the next draw
+    pub fn redraw(&mut self) {
+        self.ctx.mark_dirty(Rectangle::new(Point::zero(), self.viewport_size.into()))
+    }
+
+    fn update_time(&mut self) {
+        self.ctx.now = self.clock.as_ref().map(|clock| clock.now());
+    }
+
+    fn relayout(&mut self) {
+        // Modal overlays trap focus again on layout
+        self.ctx.focus_trap = None;
+
+        let root_node = self.root.layout(
+            &mut self.ctx,
+            &mut self.root_state,
+            &self.styler,
+            &Limits::only_max(self.viewport_size),
+            &Viewport { size: self.viewport_size },
+        );
+        self.ctx.layout_invalid = false;
+        self.ctx.focus_order = self.root.tree_ids();
+
+        self.ctx.enter_focus_trap();
+
+        if root_node != self.root_node {
+            self.root_node = root_node;
+            self.redraw();
+        }
+    }
+
+    /// Drop the root element, keeping everything needed to rebuild the UI
+    pub fn into_cache(self) -> UiCache<Message, S> {
+        UiCache {
+            viewport_size: self.viewport_size,
+            root_node: self.root_node,
+            root_state: self.root_state,
+            styler: self.styler,
+            ctx: self.ctx,
+            on_exit: self.on_exit,
+            clock: self.clock,
+            timers: self.timers,
+            gestures: self.gestures,
         }
     }
 
@@ -140,60 +616,192 @@ impl<'a, Message, R: Renderer, E: Event, S: Styler<R::Color>> UI<'a, Message, R,
         self
     }
 
+    /// Time source for animations. Without it animated values change
+    /// instantly.
+    pub fn clock(mut self, clock: impl Clock + 'static) -> Self {
+        self.clock = Some(Box::new(clock));
+        self
+    }
+
+    /// Move focus from the last element to the first one and back instead
+    /// of stopping at the ends
+    pub fn wrap_focus(mut self) -> Self {
+        self.ctx.root_scope = FocusScope::Wrap;
+        self
+    }
+
+    /// Replace active timers, see [`Subscription`]. Timers fire on `tick`
+    /// and only if UI has a clock.
+    pub fn subscribe(&mut self, subscriptions: impl IntoIterator<Item = Subscription<Message>>) {
+        self.timers.subscribe(subscriptions)
+    }
+
+    /// Timings of focus button gestures, see [`Gesture`](crate::gesture::Gesture).
+    /// Gestures are recognized only if UI has a clock.
+    pub fn gestures(mut self, gestures: Gestures) -> Self {
+        self.gestures = gestures;
+        self
+    }
+
+    /// The earliest time some subscription fires or a gesture of the held
+    /// button is recognized at
+    pub fn next_deadline(&self) -> Option<Instant> {
+        earliest(self.timers.next_deadline(), self.gestures.next_deadline())
+    }
+
+    /// Take the earliest published message, messages come in the order
+    /// they were published
     pub fn deque_message(&mut self) -> Option<Message> {
-        self.ctx.message_pool.pop_back()
+        self.ctx.message_pool.pop_front()
     }
 
     pub fn tick(&mut self, events: impl Iterator<Item = E>) {
+        self.update_time();
+
+        if self.ctx.layout_invalid {
+            self.relayout();
+        }
+
+        if let Some(now) = self.ctx.now {
+            let ctx = &mut self.ctx;
+            self.timers.poll(now, |message| ctx.publish(message));
+        }
+
+        let mut trace = Vec::new();
+
         for event in events {
-            if let core::ops::ControlFlow::Continue(propagate) = self.root.on_event(
-                &mut self.ctx,
-                event.clone(),
-                &mut self.root_state,
-                Layout::new(&self.root_node),
-            ) {
-                match propagate {
-                    Propagate::BubbleUp(bubble_origin, bubbled) => {
-                        // debug!("Capture Bubble up event {bubbled:?} from {bubble_origin:?}");
-                        if let Some(common) = bubbled.as_common() {
-                            match common {
-                                crate::event::CommonEvent::FocusMove(offset) => {
-                                    let tree = self.root.tree_ids();
-                                    let current = tree
-                                        .iter()
-                                        .position(|&id| id == bubble_origin)
-                                        .unwrap_or(0)
-                                        as i32;
-                                    let next_focus = current
-                                        .saturating_add(offset)
-                                        .clamp(0, tree.len().saturating_sub(1) as i32);
-                                    self.ctx.focus(tree[next_focus as usize]);
-                                    // return Capture::Captured.into();
-                                    return;
-                                },
-                                _ => {},
-                            }
-                        }
-                    },
-                    Propagate::Ignored => {
-                        if let Some(common) = event.as_common() {
-                            match common {
-                                crate::event::CommonEvent::Exit => {},
-                                _ => {},
-                            }
-                        }
-                    },
-                }
-            } else {
-                // debug!("Some element captured event {event:?}");
+            if let Some(CommonEvent::ToggleDebug) = event.as_common() {
+                self.toggle_debug();
+                continue;
+            }
+
+            self.timers.reset_idle();
+
+            let gesture = match (self.ctx.now, event.as_common()) {
+                (Some(now), Some(common)) => self.gestures.input(common, now),
+                _ => None,
+            };
+
+            self.dispatch_traced(event, &mut trace);
+
+            // Double press comes right after the release completing it
+            if let Some(gesture) = gesture {
+                self.dispatch_traced(CommonEvent::Gesture(gesture).into(), &mut trace);
             }
-            // TODO: Debug log "ignored event"
-            // Propagate::Ignored.into()
+        }
+
+        // Long press and repeat come while the button is held
+        if let Some(now) = self.ctx.now {
+            while let Some(gesture) = self.gestures.poll(now) {
+                self.dispatch_traced(CommonEvent::Gesture(gesture).into(), &mut trace);
+            }
+        }
+
+        if !trace.is_empty() {
+            self.ctx.event_trace = trace;
+            self.redraw();
+        }
+    }
+
+    /// Dispatch event, recording its route in debug mode
+    fn dispatch_traced(&mut self, event: E, trace: &mut Vec<EventTrace>) {
+        let common = event.as_common();
+        let route = self.dispatch(event);
+
+        if self.ctx.debug {
+            trace.push(EventTrace { event: common, route });
+        }
+    }
+
+    fn dispatch(&mut self, event: E) -> EventRoute {
+        // Previous event could change the tree, e.g. navigate to other page
+        if self.ctx.layout_invalid {
+            self.relayout();
+        }
+
+        self.ctx.captured = None;
+
+        // Pointer press goes to the element under it
+        if let Some(CommonEvent::PointerDown(point)) = event.as_common() {
+            self.focus_at(point);
+        }
+
+        // Overlays are above the tree, so they receive events first. Modal
+        // ones don't let events pass to the tree.
+        let response = match self.root.overlay_event(
+            &mut self.ctx,
+            event.clone(),
+            &mut self.root_state,
+            Layout::new(&self.root_node),
+        ) {
+            Some(ControlFlow::Continue(Propagate::Ignored)) | None if !self.ctx.is_modal() => {
+                self.root.on_event(
+                    &mut self.ctx,
+                    event.clone(),
+                    &mut self.root_state,
+                    Layout::new(&self.root_node),
+                )
+            },
+            response => response.unwrap_or(ControlFlow::Continue(Propagate::Ignored)),
+        };
+
+        if let ControlFlow::Continue(propagate) = response {
+            match propagate {
+                Propagate::BubbleUp(bubble_origin, bubbled) => {
+                    // debug!("Capture Bubble up event {bubbled:?} from {bubble_origin:?}");
+                    if let Some(CommonEvent::FocusMove(_) | CommonEvent::FocusDirection(_)) =
+                        bubbled.as_common()
+                    {
+                        self.move_focus(bubble_origin, bubbled);
+                    }
+
+                    EventRoute::BubbledUp(bubble_origin)
+                },
+                Propagate::Ignored => {
+                    if event.as_common() == Some(CommonEvent::Exit) {
+                        self.exit()
+                    }
+
+                    EventRoute::Ignored
+                },
+            }
+        } else {
+            // debug!("Some element captured event {event:?}");
+            EventRoute::Captured(self.ctx.captured.take())
+        }
+        // TODO: Debug log "ignored event"
+    }
+
+    /// Move focus bubbled up to the root, the whole UI is a focus scope
+    fn move_focus(&mut self, origin: ElId, event: E) {
+        let order = self.ctx.focusable().to_vec();
+        let root_scope = self.ctx.root_scope;
+
+        let bounds = || {
+            let mut bounds = Vec::new();
+            self.root.focus_bounds(&mut self.root_state, Layout::new(&self.root_node), &mut bounds);
+            bounds.retain(|(id, _)| order.contains(id));
+            bounds
+        };
+
+        let _ = root_scope.move_focus(&mut self.ctx, &order, bounds, origin, event);
+    }
+
+    /// Focus the innermost focusable element under `point`, if any
+    fn focus_at(&mut self, point: Point) {
+        let mut bounds = Vec::new();
+        self.root.focus_bounds(&mut self.root_state, Layout::new(&self.root_node), &mut bounds);
+
+        let focusable = self.ctx.focusable();
+        let hit = focus::hit(point, bounds.into_iter().filter(|(id, _)| focusable.contains(id)));
+
+        if let Some(id) = hit {
+            self.ctx.focus(id);
         }
     }
 
     pub fn auto_focus(&mut self) {
-        if let Some(first_el) = self.root.tree_ids().first().copied() {
+        if let Some(first_el) = self.ctx.focusable().first().copied() {
             self.ctx.focus(first_el)
         }
     }
@@ -201,6 +809,32 @@ impl<'a, Message, R: Renderer, E: Event, S: Styler<R::Color>> UI<'a, Message, R,
     pub fn focus(&mut self, id: ElId) {
         self.ctx.focus(id)
     }
+
+    /// Run `on_exit` callback. It is called only once, following exits do
+    /// nothing.
+    pub fn exit(&mut self) {
+        if let Some(on_exit) = self.on_exit.take() {
+            on_exit()
+        }
+    }
+
+    /// Execute action, e.g. one returned from `update`. Widget actions are
+    /// passed down the tree to the widget with the target id.
+    pub fn perform(&mut self, action: impl Into<Action>) {
+        match action.into() {
+            Action::None => {},
+            Action::Exit => self.exit(),
+            Action::Widget(WidgetAction::Focus(id)) => self.focus(id),
+            Action::Widget(action) => {
+                self.root.perform(
+                    &mut self.ctx,
+                    &action,
+                    &mut self.root_state,
+                    Layout::new(&self.root_node),
+                );
+            },
+        }
+    }
 }
 
 /// Does not have events
@@ -250,3 +884,177 @@ renderer_colors! {
     rgb666: Rgb666,
     rgb888: Rgb888
 }
+
+#[cfg(test)]
+mod tests {
+    use embedded_graphics::{
+        geometry::{Point, Size},
+        pixelcolor::{Rgb888, RgbColor},
+        primitives::Rectangle,
+        Drawable, Pixel,
+    };
+
+    use alloc::rc::Rc;
+    use core::{cell::Cell, time::Duration};
+
+    use crate::{
+        action::Action,
+        col,
+        el::ElId,
+        event::CommonEvent,
+        helpers::{button, checkbox, scrollable_v, select_v},
+        render::DrawTargetRenderer,
+        size::Length,
+        testing::{click, harness, Event, Framebuffer, Harness, View},
+        theme::Theme,
+        widget::Widget,
+    };
+
+    use super::{UiCtx, UI};
+
+    fn rect(x: i32, y: i32, width: u32, height: u32) -> Rectangle {
+        Rectangle::new(Point::new(x, y), Size::new(width, height))
+    }
+
+    #[test]
+    fn unites_dirty_regions() {
+        let mut ctx = UiCtx::<()>::new();
+        ctx.mark_dirty(rect(0, 0, 0, 0));
+        assert_eq!(ctx.dirty, None);
+
+        ctx.mark_dirty(rect(0, 0, 2, 2));
+        ctx.mark_dirty(rect(4, 4, 2, 2));
+        assert_eq!(ctx.dirty, Some(rect(0, 0, 6, 6)));
+
+        // Elements are dirty only if they intersect the region being redrawn
+        assert!(!ctx.is_dirty(rect(2, 2, 1, 1)));
+        ctx.redrawing = ctx.dirty.take();
+        assert!(ctx.is_dirty(rect(2, 2, 1, 1)));
+        assert!(!ctx.is_dirty(rect(6, 0, 2, 2)));
+    }
+
+    #[test]
+    fn redraws_only_dirty_elements() {
+        let mut ui: UI<(), DrawTargetRenderer<Rgb888>, Event, Theme<Rgb888>> =
+            UI::new(col![button("A"), button("B")], Size::new(64, 32).into());
+        let mut frame = Framebuffer::new(Size::new(64, 32), Rgb888::default());
+        ui.draw(&mut frame);
+
+        // Scribble over both buttons, clean ones are not redrawn
+        let (first, second) = (Point::new(2, 8), Point::new(2, 24));
+        for point in [first, second] {
+            Pixel(point, Rgb888::RED).draw(&mut frame).unwrap();
+        }
+        ui.draw(&mut frame);
+        assert_eq!(frame.pixel(first), Some(Rgb888::RED));
+
+        // Focusing and pressing the first button marks only its bounds dirty
+        ui.auto_focus();
+        ui.tick([CommonEvent::FocusButtonDown.into()].into_iter());
+        ui.draw(&mut frame);
+        assert_ne!(frame.pixel(first), Some(Rgb888::RED));
+        assert_eq!(frame.pixel(second), Some(Rgb888::RED));
+    }
+
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    enum Msg {
+        Checked(bool),
+        Chosen(u8),
+    }
+
+    fn id(el: &View<Msg>) -> ElId {
+        el.tree_ids()[0]
+    }
+
+    #[test]
+    fn performs_focus_and_exit() {
+        let exits = Rc::new(Cell::new(0));
+        let on_exit = {
+            let exits = exits.clone();
+            move || exits.set(exits.get() + 1)
+        };
+
+        let mut ui: UI<Msg, DrawTargetRenderer<Rgb888>, Event, Theme<Rgb888>> = UI::new(
+            col![button("A").identify("a"), button("B").identify("b")],
+            Size::new(64, 32).into(),
+        )
+        .on_exit(on_exit);
+
+        ui.perform(Action::focus("b".into()));
+        assert_eq!(ui.ctx.focused(), Some("b".into()));
+
+        ui.perform(Action::None);
+        assert_eq!(exits.get(), 0);
+
+        // Exit callback runs only once
+        ui.perform(Action::Exit);
+        ui.perform(Action::Exit);
+        assert_eq!(exits.get(), 1);
+    }
+
+    #[test]
+    fn activates_and_resets_widgets() {
+        let check: View<Msg> = checkbox(Msg::Checked).into();
+        let select: View<Msg> =
+            select_v([1u8, 2, 3]).on_change(|option| Msg::Chosen(*option)).into();
+        let (check_id, select_id) = (id(&check), id(&select));
+        let mut harness = Harness::new(col![check, select], Size::new(64, 64));
+
+        // Activated select changes option without being clicked
+        harness.ui().perform(Action::focus(select_id));
+        harness.ui().perform(Action::activate(select_id));
+        let mut harness = harness.events([CommonEvent::FocusMove(1).into()]);
+        assert_eq!(harness.messages(), [Msg::Chosen(2)]);
+
+        harness.ui().perform(Action::deactivate(select_id));
+        let mut harness = harness.events([CommonEvent::FocusMove(1).into()]);
+        assert_eq!(harness.messages(), []);
+
+        harness.ui().perform(Action::focus(check_id));
+        let mut harness = harness.events(click());
+        assert_eq!(harness.messages(), [Msg::Checked(true)]);
+
+        // Reset checkbox is unchecked again, so the click checks it
+        harness.ui().perform(Action::reset(check_id));
+        let mut harness = harness.events(click());
+        assert_eq!(harness.messages(), [Msg::Checked(true)]);
+    }
+
+    #[test]
+    fn scrolls_to_and_redraws_widgets() {
+        let small = |label| button(label).width(Length::Shrink).height(Length::Shrink);
+        let last: View<Msg> = small("Last").into();
+        let last_id = id(&last);
+        let content = col![
+            small("A").identify("first"),
+            small("B"),
+            small("C"),
+            small("D"),
+            small("E"),
+            last
+        ]
+        .height(Length::Shrink);
+        let mut harness = harness(scrollable_v(content));
+        let top = harness.frame().clone();
+
+        // Scroll is animated, so frames are compared after its transition
+        harness.ui().perform(Action::scroll_to(last_id));
+        let mut harness = harness.advance(Duration::from_secs(1));
+        assert!(harness.frame() != &top);
+
+        harness.ui().perform(Action::scroll_to("first".into()));
+        let mut harness = harness.advance(Duration::from_secs(1));
+        assert!(harness.frame() == &top);
+
+        // Clean element is redrawn only by request
+        let point = Point::new(2, 2);
+        let mut frame = harness.frame().clone();
+        Pixel(point, Rgb888::RED).draw(&mut frame).unwrap();
+        harness.ui().draw(&mut frame);
+        assert_eq!(frame.pixel(point), Some(Rgb888::RED));
+
+        harness.ui().perform(Action::redraw("first".into()));
+        harness.ui().draw(&mut frame);
+        assert_ne!(frame.pixel(point), Some(Rgb888::RED));
+    }
+}
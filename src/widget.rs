@@ -1,6 +1,8 @@
 use alloc::vec::Vec;
+use embedded_graphics::primitives::Rectangle;
 
 use crate::{
+    action::{ActionResult, WidgetAction},
     debug::WidgetDebug,
     el::ElId,
     event::{Event, EventResponse, Propagate},
@@ -11,17 +13,27 @@ use crate::{
     ui::UiCtx,
 };
 
-// pub struct Overlay {
-//     bounds: Bounds,
-//     children: Vec<Overlay>,
-// }
-
 pub trait Widget<Message, R, E: Event, S>
 where
     R: Renderer,
 {
     fn id(&self) -> Option<ElId>;
     fn tree_ids(&self) -> Vec<ElId>;
+    /// Collect bounds of focusable elements in viewport for spatial focus
+    /// navigation, in the same order as [`Widget::tree_ids`]. Containers
+    /// must pass it to their children.
+    fn focus_bounds(
+        &self,
+        state: &mut StateNode,
+        layout: Layout,
+        bounds: &mut Vec<(ElId, Rectangle)>,
+    ) {
+        let _ = state;
+
+        if let Some(id) = self.id() {
+            bounds.push((id, layout.bounds()));
+        }
+    }
     fn size(&self, viewport: &Viewport) -> Size<Length>;
     fn position(&self) -> Position {
         Position::Relative
@@ -60,6 +72,55 @@ where
         Propagate::Ignored.into()
     }
 
+    /// Draw overlays of this widget or its children above the whole tree.
+    /// Called after the tree is drawn, containers must pass it to their
+    /// children. See [`Overlay`](crate::widgets::overlay::Overlay).
+    fn draw_overlay(
+        &self,
+        ctx: &mut UiCtx<Message>,
+        state: &mut StateNode,
+        renderer: &mut R,
+        styler: &S,
+        layout: Layout,
+        viewport: &Viewport,
+    ) {
+        let _ = ctx;
+        let _ = state;
+        let _ = renderer;
+        let _ = styler;
+        let _ = layout;
+        let _ = viewport;
+    }
+
+    /// Pass event to open overlays before the tree. `None` if there are no
+    /// open overlays, containers must pass it to their children.
+    fn overlay_event(
+        &mut self,
+        ctx: &mut UiCtx<Message>,
+        event: E,
+        state: &mut StateNode,
+        layout: Layout,
+    ) -> Option<EventResponse<E>> {
+        let _ = ctx;
+        let _ = event;
+        let _ = state;
+        let _ = layout;
+
+        None
+    }
+
+    /// Perform action targeting this widget or one of its children.
+    /// Containers must pass actions to their children.
+    fn perform(
+        &mut self,
+        ctx: &mut UiCtx<Message>,
+        action: &WidgetAction,
+        state: &mut StateNode,
+        layout: Layout,
+    ) -> ActionResult {
+        perform_default(self, ctx, action, state, layout)
+    }
+
     fn state_tag(&self) -> StateTag {
         StateTag::stateless()
     }
@@ -70,7 +131,47 @@ where
         vec![]
     }
 
-    fn debug(&self, _layout: Layout) -> Option<WidgetDebug<R::Color>> {
-        None
+    /// Info debug mode draws and [`UI::dump_tree`](crate::ui::UI::dump_tree)
+    /// prints, see [`UI::debug`](crate::ui::UI::debug). Containers must pass
+    /// it to their children.
+    fn debug(
+        &self,
+        state: &mut StateNode,
+        layout: Layout,
+        viewport: &Viewport,
+    ) -> Option<WidgetDebug<R::Color>> {
+        Some(WidgetDebug::new(self, state, layout, viewport))
+    }
+}
+
+/// Handling of actions common for all widgets. Widgets overriding
+/// [`Widget::perform`] should fall back to it.
+pub fn perform_default<Message, R, E, S, W>(
+    widget: &W,
+    ctx: &mut UiCtx<Message>,
+    action: &WidgetAction,
+    state: &mut StateNode,
+    layout: Layout,
+) -> ActionResult
+where
+    R: Renderer,
+    E: Event,
+    W: Widget<Message, R, E, S> + ?Sized,
+{
+    match *action {
+        WidgetAction::Reset(id) if widget.id() == Some(id) => {
+            state.state = widget.state();
+            state.children = widget.state_children();
+            ctx.mark_dirty(layout.bounds());
+            ActionResult::Done
+        },
+        WidgetAction::Redraw(id) if widget.id() == Some(id) => {
+            ctx.mark_dirty(layout.bounds());
+            ActionResult::Done
+        },
+        WidgetAction::ScrollTo(id) if widget.id() == Some(id) => {
+            ActionResult::Found(layout.bounds())
+        },
+        _ => ActionResult::Ignored,
     }
 }
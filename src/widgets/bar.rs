@@ -3,6 +3,7 @@
 use embedded_graphics::primitives::Rectangle;
 
 use crate::{
+    animation::{Animated, Transition},
     axis::{Axial, Axis},
     block::{Block, Border},
     el::El,
@@ -11,11 +12,18 @@ use crate::{
     palette::PaletteColor,
     render::Renderer,
     size::{Length, Size},
+    state::{State, StateTag},
     style::component_style,
     theme::Theme,
     widget::Widget,
 };
 
+/// Shown value, animated towards the actual one
+#[derive(Clone, Copy, Debug, Default)]
+struct BarState {
+    value: Option<Animated<f32>>,
+}
+
 #[derive(Clone, Copy)]
 pub struct BarStatus {
     /// You can style bar depending on its value
@@ -51,6 +59,7 @@ where
     class: S::Class<'a>,
     value: f32,
     axis: Axis,
+    transition: Transition,
 }
 
 impl<'a, Message, R, E, S> From<Bar<'a, R, S>> for El<'a, Message, R, E, S>
@@ -75,6 +84,7 @@ where
             class: S::default(),
             value: 0.5,
             axis,
+            transition: Transition::none(),
         }
     }
 
@@ -100,6 +110,12 @@ where
         self.value = value;
         self
     }
+
+    /// Animate value changes
+    pub fn transition(mut self, transition: impl Into<Transition>) -> Self {
+        self.transition = transition.into();
+        self
+    }
 }
 
 impl<'a, Message, R, E, S> Widget<Message, R, E, S> for Bar<'a, R, S>
@@ -120,13 +136,13 @@ where
         self.size
     }
 
-    // fn state_tag(&self) -> crate::state::StateTag {
-    //     StateTag::of::<BarState>()
-    // }
+    fn state_tag(&self) -> crate::state::StateTag {
+        StateTag::of::<BarState>()
+    }
 
-    // fn state(&self) -> crate::state::State {
-    //     State::new(BarState::default())
-    // }
+    fn state(&self) -> crate::state::State {
+        State::new(BarState::default())
+    }
 
     // fn state_children(&self) -> alloc::vec::Vec<crate::state::StateNode> {
     //     vec![]
@@ -147,8 +163,8 @@ where
 
     fn draw(
         &self,
-        _ctx: &mut crate::ui::UiCtx<Message>,
-        _state: &mut crate::state::StateNode,
+        ctx: &mut crate::ui::UiCtx<Message>,
+        state: &mut crate::state::StateNode,
         renderer: &mut R,
         styler: &S,
         layout: crate::layout::Layout,
@@ -156,10 +172,17 @@ where
     ) {
         let bounds = layout.bounds();
 
-        let style = styler.style(&self.class, BarStatus { value: self.value });
+        let value = ctx.animate(
+            &mut state.get_mut::<BarState>().value,
+            self.value,
+            self.transition,
+            bounds,
+        );
+
+        let style = styler.style(&self.class, BarStatus { value });
 
         let size = bounds.size.into_axial(self.axis);
-        let length = (self.value * size.main() as f32) as u32;
+        let length = (value * size.main() as f32) as u32;
         let bar_rect = Into::<Rectangle>::into(bounds);
         let bar_rect = match self.axis {
             Axis::X => bar_rect.resized_width(length, embedded_graphics::geometry::AnchorX::Left),
@@ -1,10 +1,15 @@
 use alloc::vec::Vec;
+use embedded_graphics::primitives::Rectangle;
 
 use crate::{
+    action::{ActionResult, WidgetAction},
     align::Align,
+    animation::{Animated, Transition},
     block::BoxModel,
+    debug::WidgetDebug,
     el::{El, ElId},
     event::{Capture, CommonEvent, Event, EventResponse, Propagate},
+    gesture::Gesture,
     layout::{Layout, Viewport},
     padding::Padding,
     palette::PaletteColor,
@@ -14,17 +19,21 @@ use crate::{
     style::component_style,
     theme::Theme,
     ui::UiCtx,
-    widget::Widget,
+    widget::{perform_default, Widget},
 };
 
-// TODO: Double-click (needs time source)
+#[derive(Debug)]
 struct ButtonState {
     pressed: bool,
+    /// Press is handled by long press or repeat, so release is not a click
+    consumed: bool,
+    /// Outline width animated on focus and press
+    outline_width: Option<Animated<u32>>,
 }
 
 impl Default for ButtonState {
     fn default() -> Self {
-        Self { pressed: false }
+        Self { pressed: false, consumed: false, outline_width: None }
     }
 }
 
@@ -78,6 +87,10 @@ where
     padding: Padding,
     class: S::Class<'a>,
     on_press: Option<Message>,
+    on_long_press: Option<Message>,
+    on_double_press: Option<Message>,
+    repeat: bool,
+    transition: Transition,
 }
 
 impl<'a, Message, R, E, S> Button<'a, Message, R, E, S>
@@ -99,6 +112,10 @@ where
             padding,
             class: S::default(),
             on_press: None,
+            on_long_press: None,
+            on_double_press: None,
+            repeat: false,
+            transition: Transition::none(),
         }
     }
 
@@ -122,6 +139,33 @@ where
         self
     }
 
+    /// Published instead of `on_press` when the button is held, see
+    /// [`Gesture::LongPress`]
+    pub fn on_long_press(mut self, on_long_press: impl Into<Message>) -> Self {
+        self.on_long_press = Some(on_long_press.into());
+        self
+    }
+
+    /// Published after two `on_press` clicks coming quickly one after
+    /// another, see [`Gesture::DoublePress`]
+    pub fn on_double_press(mut self, on_double_press: impl Into<Message>) -> Self {
+        self.on_double_press = Some(on_double_press.into());
+        self
+    }
+
+    /// Publish `on_press` repeatedly while the button is held, e.g. to
+    /// increment value
+    pub fn repeat(mut self) -> Self {
+        self.repeat = true;
+        self
+    }
+
+    /// Animate outline on focus and press
+    pub fn transition(mut self, transition: impl Into<Transition>) -> Self {
+        self.transition = transition.into();
+        self
+    }
+
     pub fn store_id(self, id: &mut ElId) -> Self {
         *id = self.id;
         self
@@ -154,6 +198,28 @@ where
         ids
     }
 
+    fn focus_bounds(
+        &self,
+        state: &mut StateNode,
+        layout: Layout,
+        bounds: &mut Vec<(ElId, Rectangle)>,
+    ) {
+        bounds.push((self.id, layout.bounds()));
+        self.content.focus_bounds(&mut state.children[0], layout.first_child(), bounds)
+    }
+
+    fn debug(
+        &self,
+        state: &mut StateNode,
+        layout: Layout,
+        viewport: &Viewport,
+    ) -> Option<WidgetDebug<R::Color>> {
+        let debug = WidgetDebug::new(self, state, layout.clone(), viewport);
+        let content = self.content.debug(&mut state.children[0], layout.first_child(), viewport);
+
+        Some(debug.children([content]))
+    }
+
     fn size(&self, _viewport: &Viewport) -> Size<Length> {
         self.size
     }
@@ -170,6 +236,24 @@ where
         vec![StateNode::new(&self.content)]
     }
 
+    fn perform(
+        &mut self,
+        ctx: &mut UiCtx<Message>,
+        action: &WidgetAction,
+        state: &mut StateNode,
+        layout: Layout,
+    ) -> ActionResult {
+        match self.content.perform(
+            ctx,
+            action,
+            &mut state.children[0],
+            layout.clone().first_child(),
+        ) {
+            ActionResult::Ignored => perform_default(self, ctx, action, state, layout),
+            result => result,
+        }
+    }
+
     fn on_event(
         &mut self,
         ctx: &mut UiCtx<Message>,
@@ -177,6 +261,8 @@ where
         state: &mut StateNode,
         layout: Layout,
     ) -> EventResponse<E> {
+        let bounds = layout.bounds();
+
         match self.content.on_event(
             ctx,
             event.clone(),
@@ -187,11 +273,16 @@ where
                 Some(common) => match common {
                     // Tell parent that this child is the currently focused so parent can use it
                     // as an offset of focus
-                    CommonEvent::FocusMove(_) if ctx.is_focused(self) => {
+                    CommonEvent::FocusMove(_) | CommonEvent::FocusDirection(_)
+                        if ctx.is_focused(self) =>
+                    {
                         Propagate::BubbleUp(self.id, event).into()
                     },
                     CommonEvent::FocusButtonDown if ctx.is_focused(self) => {
-                        state.get_mut::<ButtonState>().pressed = true;
+                        let state = state.get_mut::<ButtonState>();
+                        state.pressed = true;
+                        state.consumed = false;
+                        ctx.mark_dirty(bounds);
 
                         Capture::Captured.into()
                     },
@@ -201,11 +292,15 @@ where
                         // - Focus button was down on it
                         // - Focus button released on it
 
-                        let pressed = state.get::<ButtonState>().pressed;
+                        // - Press wasn't handled as long press or repeat
+
+                        let ButtonState { pressed, consumed, .. } = *state.get::<ButtonState>();
 
                         state.get_mut::<ButtonState>().pressed = false;
 
-                        if pressed {
+                        if pressed && !consumed {
+                            ctx.mark_dirty(bounds);
+
                             if let Some(on_press) = self.on_press.clone() {
                                 ctx.publish(on_press);
                                 return Capture::Captured.into();
@@ -214,12 +309,74 @@ where
 
                         Propagate::Ignored.into()
                     },
+                    CommonEvent::PointerDown(point)
+                        if ctx.is_focused(self) && bounds.contains(point) =>
+                    {
+                        state.get_mut::<ButtonState>().pressed = true;
+                        ctx.mark_dirty(bounds);
+
+                        Capture::Captured.into()
+                    },
+                    // Pressed button is clicked only if pointer is released on it
+                    CommonEvent::PointerUp(point) if state.get::<ButtonState>().pressed => {
+                        state.get_mut::<ButtonState>().pressed = false;
+                        ctx.mark_dirty(bounds);
+
+                        if let Some(on_press) = self.on_press.clone() {
+                            if bounds.contains(point) {
+                                ctx.publish(on_press);
+                            }
+                        }
+
+                        Capture::Captured.into()
+                    },
+                    CommonEvent::Gesture(Gesture::LongPress)
+                        if ctx.is_focused(self)
+                            && state.get::<ButtonState>().pressed
+                            && self.on_long_press.is_some() =>
+                    {
+                        state.get_mut::<ButtonState>().consumed = true;
+                        ctx.publish(self.on_long_press.clone().unwrap());
+
+                        Capture::Captured.into()
+                    },
+                    CommonEvent::Gesture(Gesture::Repeat)
+                        if ctx.is_focused(self)
+                            && state.get::<ButtonState>().pressed
+                            && self.repeat =>
+                    {
+                        state.get_mut::<ButtonState>().consumed = true;
+                        if let Some(on_press) = self.on_press.clone() {
+                            ctx.publish(on_press);
+                        }
+
+                        Capture::Captured.into()
+                    },
+                    CommonEvent::Gesture(Gesture::DoublePress) if ctx.is_focused(self) => {
+                        match self.on_double_press.clone() {
+                            Some(on_double_press) => {
+                                ctx.publish(on_double_press);
+                                Capture::Captured.into()
+                            },
+                            None => Propagate::Ignored.into(),
+                        }
+                    },
+                    CommonEvent::PointerDown(_)
+                    | CommonEvent::PointerMove(_)
+                    | CommonEvent::PointerUp(_)
+                    | CommonEvent::Gesture(_)
+                    | CommonEvent::ToggleDebug => Propagate::Ignored.into(),
                     CommonEvent::FocusButtonDown
                     | CommonEvent::FocusButtonUp
                     | CommonEvent::FocusMove(_)
-                    | CommonEvent::Exit => {
+                    | CommonEvent::FocusDirection(_)
+                    | CommonEvent::Exit
+                    | CommonEvent::Back => {
                         // Reset pressed state on click on other element
-                        state.get_mut::<ButtonState>().pressed = false;
+                        if state.get::<ButtonState>().pressed {
+                            state.get_mut::<ButtonState>().pressed = false;
+                            ctx.mark_dirty(bounds);
+                        }
 
                         Propagate::Ignored.into()
                     },
@@ -263,7 +420,13 @@ where
     ) {
         let bounds = layout.bounds();
 
-        let style = styler.style(&self.class, self.status(ctx, state));
+        let mut style = styler.style(&self.class, self.status(ctx, state));
+        style.outline.width = ctx.animate(
+            &mut state.get_mut::<ButtonState>().outline_width,
+            style.outline.width,
+            self.transition,
+            bounds,
+        );
 
         renderer.block(style.border.into_block(bounds, style.background));
 
@@ -278,6 +441,35 @@ where
 
         renderer.block(style.outline.into_outline(bounds));
     }
+
+    fn draw_overlay(
+        &self,
+        ctx: &mut UiCtx<Message>,
+        state: &mut StateNode,
+        renderer: &mut R,
+        styler: &S,
+        layout: Layout,
+        viewport: &Viewport,
+    ) {
+        self.content.draw_overlay(
+            ctx,
+            &mut state.children[0],
+            renderer,
+            styler,
+            layout.first_child(),
+            viewport,
+        )
+    }
+
+    fn overlay_event(
+        &mut self,
+        ctx: &mut UiCtx<Message>,
+        event: E,
+        state: &mut StateNode,
+        layout: Layout,
+    ) -> Option<EventResponse<E>> {
+        self.content.overlay_event(ctx, event, &mut state.children[0], layout.first_child())
+    }
 }
 
 impl<'a, Message, R, E, S> From<Button<'a, Message, R, E, S>> for El<'a, Message, R, E, S>
@@ -291,3 +483,86 @@ where
         Self::new(value)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use core::time::Duration;
+
+    use embedded_graphics::geometry::Point;
+
+    use crate::{
+        event::CommonEvent,
+        helpers::button,
+        size::Length,
+        testing::{click, harness, tap, Script},
+    };
+
+    fn press(script: Script<u8>) -> Script<u8> {
+        script.events([CommonEvent::FocusButtonDown.into()])
+    }
+
+    fn release(script: Script<u8>) -> Script<u8> {
+        script.events([CommonEvent::FocusButtonUp.into()])
+    }
+
+    #[test]
+    fn clicks_on_release_inside() {
+        let (inside, outside) = (Point::new(10, 16), Point::new(50, 16));
+        let mut script =
+            harness::<u8>(button("OK").width(Length::Fixed(20)).on_press(1)).events(tap(inside));
+        assert_eq!(script.messages(), [1]);
+
+        // Press moved out of the button is cancelled
+        let mut script = script.events([
+            CommonEvent::PointerDown(inside).into(),
+            CommonEvent::PointerUp(outside).into(),
+        ]);
+        assert_eq!(script.messages(), []);
+
+        let mut script = script.events([
+            CommonEvent::PointerDown(outside).into(),
+            CommonEvent::PointerUp(inside).into(),
+        ]);
+        assert_eq!(script.messages(), []);
+    }
+
+    #[test]
+    fn long_press_is_not_a_click() {
+        let script = harness(button("").on_press(1).on_long_press(2)).auto_focus();
+        let mut script = press(script).advance(Duration::from_millis(500));
+        assert_eq!(script.messages(), [2]);
+
+        let mut script = release(script);
+        assert_eq!(script.messages(), []);
+    }
+
+    #[test]
+    fn double_press_follows_two_clicks() {
+        let mut script = harness::<u8>(button("").on_press(1).on_double_press(2))
+            .auto_focus()
+            .events(click())
+            .advance(Duration::from_millis(100))
+            .events(click());
+        assert_eq!(script.messages(), [1, 1, 2]);
+
+        // Too late for a double press
+        let mut script = script.advance(Duration::from_millis(400)).events(click());
+        assert_eq!(script.messages(), [1]);
+    }
+
+    #[test]
+    fn repeats_press_while_held() {
+        let script = harness(button("").on_press(1).repeat()).auto_focus();
+        let mut script = press(script).advance(Duration::from_millis(400));
+        assert_eq!(script.messages(), []);
+
+        let mut script = script
+            .advance(Duration::from_millis(100))
+            .advance(Duration::from_millis(100))
+            .advance(Duration::from_millis(100));
+        assert_eq!(script.messages(), [1, 1, 1]);
+
+        let mut script = release(script);
+        assert_eq!(script.messages(), []);
+    }
+}
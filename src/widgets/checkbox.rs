@@ -18,7 +18,7 @@ use crate::widget::Widget;
 
 use super::icon::{Icon, IconStyler};
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Debug)]
 pub struct CheckboxState {
     pressed: bool,
     checked: bool,
@@ -162,18 +162,19 @@ where
         ctx: &mut UiCtx<Message>,
         event: E,
         state: &mut StateNode,
-        _layout: Layout,
+        layout: Layout,
     ) -> crate::event::EventResponse<E> {
         let focused = UiCtx::is_focused::<R, E, S>(&ctx, self);
         let current_state = state.get::<CheckboxState>();
 
         if let Some(common) = event.as_common() {
             match common {
-                CommonEvent::FocusMove(_) if focused => {
+                CommonEvent::FocusMove(_) | CommonEvent::FocusDirection(_) if focused => {
                     return Propagate::BubbleUp(self.id, event).into()
                 },
                 CommonEvent::FocusButtonDown if focused => {
                     state.get_mut::<CheckboxState>().pressed = true;
+                    ctx.mark_dirty(layout.bounds());
                     return Capture::Captured.into();
                 },
                 CommonEvent::FocusButtonUp if focused => {
@@ -182,6 +183,8 @@ where
                     state.get_mut::<CheckboxState>().pressed = false;
 
                     if was_pressed {
+                        ctx.mark_dirty(layout.bounds());
+
                         let new_state = !state.get::<CheckboxState>().checked;
                         state.get_mut::<CheckboxState>().checked = new_state;
 
@@ -190,12 +193,41 @@ where
                         return Capture::Captured.into();
                     }
                 },
+                CommonEvent::PointerDown(point) if focused && layout.bounds().contains(point) => {
+                    state.get_mut::<CheckboxState>().pressed = true;
+                    ctx.mark_dirty(layout.bounds());
+                    return Capture::Captured.into();
+                },
+                CommonEvent::PointerUp(point) if current_state.pressed => {
+                    state.get_mut::<CheckboxState>().pressed = false;
+                    ctx.mark_dirty(layout.bounds());
+
+                    // Released outside of the checkbox, the press is cancelled
+                    if layout.bounds().contains(point) {
+                        let new_state = !state.get::<CheckboxState>().checked;
+                        state.get_mut::<CheckboxState>().checked = new_state;
+
+                        ctx.publish((self.on_change)(new_state));
+                    }
+
+                    return Capture::Captured.into();
+                },
+                CommonEvent::PointerDown(_)
+                | CommonEvent::PointerMove(_)
+                | CommonEvent::PointerUp(_)
+                | CommonEvent::Gesture(_)
+                | CommonEvent::ToggleDebug => {},
                 CommonEvent::FocusButtonDown
                 | CommonEvent::FocusButtonUp
                 | CommonEvent::FocusMove(_)
-                | CommonEvent::Exit => {
+                | CommonEvent::FocusDirection(_)
+                | CommonEvent::Exit
+                | CommonEvent::Back => {
                     // Should we reset state on any event? Or only on common
-                    state.get_mut::<CheckboxState>().pressed = false;
+                    if current_state.pressed {
+                        state.get_mut::<CheckboxState>().pressed = false;
+                        ctx.mark_dirty(layout.bounds());
+                    }
                 },
             }
         }
@@ -276,3 +308,37 @@ where
         Self::new(value)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use embedded_graphics::geometry::Point;
+
+    use crate::{
+        event::CommonEvent,
+        helpers::checkbox,
+        testing::{harness, tap},
+    };
+
+    #[test]
+    fn toggles_on_release_inside() {
+        let (inside, outside) = (Point::new(3, 3), Point::new(30, 20));
+        let mut script = harness(checkbox(|checked| checked)).events(tap(inside));
+        assert_eq!(script.messages(), [true]);
+
+        // Press moved out of the checkbox is cancelled
+        let mut script = script.events([
+            CommonEvent::PointerDown(inside).into(),
+            CommonEvent::PointerUp(outside).into(),
+        ]);
+        assert_eq!(script.messages(), []);
+
+        let mut script = script.events([
+            CommonEvent::PointerDown(outside).into(),
+            CommonEvent::PointerUp(inside).into(),
+        ]);
+        assert_eq!(script.messages(), []);
+
+        let mut script = script.events(tap(inside));
+        assert_eq!(script.messages(), [false]);
+    }
+}
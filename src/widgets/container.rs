@@ -1,9 +1,11 @@
 use crate::{
     align::Align,
     block::BoxModel,
+    debug::WidgetDebug,
     el::El,
-    event::Event,
-    layout::Layout,
+    event::{Event, Propagate},
+    focus::{FocusScope, ScopeState},
+    layout::{Layout, Viewport},
     palette::PaletteColor,
     render::Renderer,
     size::{Length, Size},
@@ -40,6 +42,7 @@ where
     h_align: Align,
     v_align: Align,
     class: S::Class<'a>,
+    focus_scope: Option<FocusScope>,
 }
 
 impl<'a, Message, R, E, S> Container<'a, Message, R, E, S>
@@ -55,6 +58,7 @@ where
             h_align: Align::Start,
             v_align: Align::Start,
             class: S::default(),
+            focus_scope: None,
         }
     }
 
@@ -77,6 +81,12 @@ where
         self.v_align = v_align.into();
         self
     }
+
+    /// Make container a focus scope, see [`FocusScope`]
+    pub fn focus_scope(mut self, focus_scope: FocusScope) -> Self {
+        self.focus_scope = Some(focus_scope);
+        self
+    }
 }
 
 impl<'a, Message, R, E, S> Widget<Message, R, E, S> for Container<'a, Message, R, E, S>
@@ -93,6 +103,27 @@ where
         self.content.tree_ids()
     }
 
+    fn focus_bounds(
+        &self,
+        state: &mut StateNode,
+        layout: Layout,
+        bounds: &mut alloc::vec::Vec<(crate::el::ElId, embedded_graphics::primitives::Rectangle)>,
+    ) {
+        self.content.focus_bounds(&mut state.children[0], layout.first_child(), bounds)
+    }
+
+    fn debug(
+        &self,
+        state: &mut StateNode,
+        layout: Layout,
+        viewport: &Viewport,
+    ) -> Option<WidgetDebug<R::Color>> {
+        let debug = WidgetDebug::new(self, state, layout.clone(), viewport);
+        let content = self.content.debug(&mut state.children[0], layout.first_child(), viewport);
+
+        Some(debug.children([content]))
+    }
+
     fn size(&self, _viewport: &crate::layout::Viewport) -> Size<Length> {
         self.size
     }
@@ -107,6 +138,10 @@ where
     ) -> crate::layout::LayoutNode {
         let style = styler.style(&self.class, ContainerStatus);
 
+        if self.focus_scope.is_some() {
+            state.get_mut::<ScopeState>().order = self.tree_ids();
+        }
+
         Layout::container(
             limits,
             self.size,
@@ -143,6 +178,25 @@ where
         );
     }
 
+    fn draw_overlay(
+        &self,
+        ctx: &mut crate::ui::UiCtx<Message>,
+        state: &mut StateNode,
+        renderer: &mut R,
+        styler: &S,
+        layout: Layout,
+        viewport: &crate::layout::Viewport,
+    ) {
+        self.content.draw_overlay(
+            ctx,
+            &mut state.children[0],
+            renderer,
+            styler,
+            layout.first_child(),
+            viewport,
+        );
+    }
+
     fn on_event(
         &mut self,
         ctx: &mut crate::ui::UiCtx<Message>,
@@ -150,15 +204,59 @@ where
         state: &mut crate::state::StateNode,
         layout: Layout,
     ) -> crate::event::EventResponse<E> {
-        self.content.on_event(ctx, event, &mut state.children[0], layout.first_child())
+        let propagate = self.content.on_event(
+            ctx,
+            event,
+            &mut state.children[0],
+            layout.clone().first_child(),
+        )?;
+
+        match (propagate, self.focus_scope) {
+            (Propagate::BubbleUp(origin, bubbled), Some(focus_scope)) => {
+                let bounds = |state: &mut StateNode| {
+                    let mut bounds = alloc::vec::Vec::new();
+                    self.focus_bounds(state, layout, &mut bounds);
+                    bounds
+                };
+
+                focus_scope.move_focus_cached(ctx, state, bounds, origin, bubbled)
+            },
+            (propagate, _) => propagate.into(),
+        }
+    }
+
+    fn overlay_event(
+        &mut self,
+        ctx: &mut crate::ui::UiCtx<Message>,
+        event: E,
+        state: &mut StateNode,
+        layout: Layout,
+    ) -> Option<crate::event::EventResponse<E>> {
+        self.content.overlay_event(ctx, event, &mut state.children[0], layout.first_child())
+    }
+
+    fn perform(
+        &mut self,
+        ctx: &mut crate::ui::UiCtx<Message>,
+        action: &crate::action::WidgetAction,
+        state: &mut StateNode,
+        layout: Layout,
+    ) -> crate::action::ActionResult {
+        self.content.perform(ctx, action, &mut state.children[0], layout.first_child())
     }
 
     fn state_tag(&self) -> crate::state::StateTag {
-        crate::state::StateTag::stateless()
+        match self.focus_scope {
+            Some(_) => crate::state::StateTag::of::<ScopeState>(),
+            None => crate::state::StateTag::stateless(),
+        }
     }
 
     fn state(&self) -> crate::state::State {
-        crate::state::State::None
+        match self.focus_scope {
+            Some(_) => crate::state::State::new(ScopeState::default()),
+            None => crate::state::State::None,
+        }
     }
 
     fn state_children(&self) -> alloc::vec::Vec<crate::state::StateNode> {
@@ -189,9 +287,10 @@ where
 
 impl<'a, T, Message, R, E, S> InsideContainerExt<'a, Message, R, E, S> for T
 where
-    R: Renderer,
-    E: Event,
-    S: ContainerStyler<R::Color>,
+    Message: 'a,
+    R: Renderer + 'a,
+    E: Event + 'a,
+    S: ContainerStyler<R::Color> + 'a,
     T: Into<El<'a, Message, R, E, S>>,
 {
     fn wrap(self) -> Container<'a, Message, R, E, S> {
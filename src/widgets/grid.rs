@@ -0,0 +1,424 @@
+use alloc::vec::Vec;
+use embedded_graphics::primitives::Rectangle;
+
+use crate::{
+    action::{ActionResult, WidgetAction},
+    block::BoxModel,
+    collections::{self, CapacityError, Children, DEFAULT_CAPACITY},
+    debug::WidgetDebug,
+    el::{El, ElId},
+    event::{Event, EventResponse, Propagate},
+    focus::{FocusScope, ScopeState},
+    layout::{Layout, LayoutNode, Limits, Position, Viewport},
+    padding::Padding,
+    render::Renderer,
+    size::{Length, Size},
+    state::{State, StateNode, StateTag},
+    ui::UiCtx,
+    widget::Widget,
+};
+
+pub use crate::layout::Cell;
+
+/// Lays out children in cells of column and row tracks, so cells of
+/// different rows are aligned, unlike in nested [`Linear`](super::linear::Linear)s.
+/// Track lengths are the same as widget lengths: fixed, shrinking to the
+/// largest child, or sharing the remaining space.
+///
+/// With `heapless` feature `N` is the maximum count of children and of
+/// tracks along each axis, see [`collections`].
+pub struct Grid<'a, Message, R: Renderer, E: Event, S, const N: usize = DEFAULT_CAPACITY> {
+    size: Size<Length>,
+    padding: Padding,
+    gap: Size,
+    columns: Children<Length, N>,
+    rows: Children<Length, N>,
+    cells: Children<Cell, N>,
+    children: Children<El<'a, Message, R, E, S>, N>,
+    focus_scope: Option<FocusScope>,
+}
+
+impl<'a, Message, R: Renderer, E: Event, S> Grid<'a, Message, R, E, S> {
+    pub fn new(
+        columns: impl IntoIterator<Item = impl Into<Length>>,
+        rows: impl IntoIterator<Item = impl Into<Length>>,
+    ) -> Self {
+        Self::with_capacity(columns, rows)
+    }
+}
+
+impl<'a, Message, R: Renderer, E: Event, S, const N: usize> Grid<'a, Message, R, E, S, N> {
+    /// Grid fitting `N` children and tracks. Panics if tracks don't fit,
+    /// see [`collections`].
+    pub fn with_capacity(
+        columns: impl IntoIterator<Item = impl Into<Length>>,
+        rows: impl IntoIterator<Item = impl Into<Length>>,
+    ) -> Self {
+        Self {
+            size: Size::fill(),
+            padding: Padding::default(),
+            gap: Size::zero(),
+            columns: collections::collect_or_overflow::<_, N>(columns.into_iter().map(Into::into)),
+            rows: collections::collect_or_overflow::<_, N>(rows.into_iter().map(Into::into)),
+            cells: Children::new(),
+            children: Children::new(),
+            focus_scope: None,
+        }
+    }
+
+    pub fn width(mut self, width: impl Into<Length>) -> Self {
+        self.size.width = width.into();
+        self
+    }
+
+    pub fn height(mut self, height: impl Into<Length>) -> Self {
+        self.size.height = height.into();
+        self
+    }
+
+    pub fn padding(mut self, padding: impl Into<Padding>) -> Self {
+        self.padding = padding.into();
+        self
+    }
+
+    /// The same gap between columns and between rows
+    pub fn gap(mut self, gap: u32) -> Self {
+        self.gap = Size::new_equal(gap);
+        self
+    }
+
+    pub fn column_gap(mut self, gap: u32) -> Self {
+        self.gap.width = gap;
+        self
+    }
+
+    pub fn row_gap(mut self, gap: u32) -> Self {
+        self.gap.height = gap;
+        self
+    }
+
+    /// Make grid a focus scope, see [`FocusScope`]
+    pub fn focus_scope(mut self, focus_scope: FocusScope) -> Self {
+        self.focus_scope = Some(focus_scope);
+        self
+    }
+
+    /// Put child into the cell. Focus order is the order children are added
+    /// in, not their placement. Panics if grid is full, prefer
+    /// [`Grid::try_cell`] with `heapless` feature, see [`collections`].
+    pub fn cell(mut self, cell: Cell, child: impl Into<El<'a, Message, R, E, S>>) -> Self {
+        if self.push_cell(cell, child.into()).is_err() {
+            #[cfg(feature = "heapless-truncate")]
+            crate::log::logger::warning!("Child dropped, capacity of {} children is full", N);
+
+            #[cfg(not(feature = "heapless-truncate"))]
+            panic!("Capacity of {} children is full, use `try_cell` to handle it", N);
+        }
+        self
+    }
+
+    /// Put child into the cell, failing if grid is full
+    pub fn try_cell(
+        mut self,
+        cell: Cell,
+        child: impl Into<El<'a, Message, R, E, S>>,
+    ) -> Result<Self, CapacityError> {
+        self.push_cell(cell, child.into())?;
+        Ok(self)
+    }
+
+    fn push_cell(
+        &mut self,
+        cell: Cell,
+        child: El<'a, Message, R, E, S>,
+    ) -> Result<(), CapacityError> {
+        collections::push::<_, N>(&mut self.children, child)
+            .map_err(|_| CapacityError { capacity: N })?;
+
+        // Cells are as many as children, so it fits too
+        let _ = collections::push::<_, N>(&mut self.cells, cell);
+        Ok(())
+    }
+
+    /// Put child into the first free cell following the previous child's
+    /// one, row by row. Cells covered by spans of other children are
+    /// skipped. Panics if grid is full, see [`Grid::cell`].
+    pub fn push(self, child: impl Into<El<'a, Message, R, E, S>>) -> Self {
+        let columns = self.columns.len().max(1);
+        let start = self.cells.last().map_or(0, |last| last.row * columns + last.column + 1);
+        let index = (start..)
+            .find(|index| {
+                !self.cells.iter().any(|cell| cell.covers(index % columns, index / columns))
+            })
+            .unwrap_or(start);
+
+        self.cell(Cell::new(index % columns, index / columns), child)
+    }
+}
+
+impl<'a, Message, R: Renderer, E: Event, S, const N: usize> Widget<Message, R, E, S>
+    for Grid<'a, Message, R, E, S, N>
+{
+    fn id(&self) -> Option<ElId> {
+        None
+    }
+
+    fn tree_ids(&self) -> Vec<ElId> {
+        self.children.iter().flat_map(|child| child.tree_ids()).collect()
+    }
+
+    fn focus_bounds(
+        &self,
+        state: &mut StateNode,
+        layout: Layout,
+        bounds: &mut Vec<(ElId, Rectangle)>,
+    ) {
+        for ((child, child_state), child_layout) in
+            self.children.iter().zip(state.children.iter_mut()).zip(layout.children())
+        {
+            child.focus_bounds(child_state, child_layout, bounds);
+        }
+    }
+
+    fn debug(
+        &self,
+        state: &mut StateNode,
+        layout: Layout,
+        viewport: &Viewport,
+    ) -> Option<WidgetDebug<R::Color>> {
+        let debug = WidgetDebug::new(self, state, layout.clone(), viewport);
+        let children =
+            self.children.iter().zip(state.children.iter_mut()).zip(layout.children()).map(
+                |((child, child_state), child_layout)| {
+                    child.debug(child_state, child_layout, viewport)
+                },
+            );
+
+        Some(debug.children(children))
+    }
+
+    fn size(&self, _viewport: &Viewport) -> Size<Length> {
+        self.size
+    }
+
+    fn state_tag(&self) -> StateTag {
+        match self.focus_scope {
+            Some(_) => StateTag::of::<ScopeState>(),
+            None => StateTag::stateless(),
+        }
+    }
+
+    fn state(&self) -> State {
+        match self.focus_scope {
+            Some(_) => State::new(ScopeState::default()),
+            None => State::None,
+        }
+    }
+
+    fn state_children(&self) -> Vec<StateNode> {
+        self.children.iter().map(|child| StateNode::new(child)).collect()
+    }
+
+    fn on_event(
+        &mut self,
+        ctx: &mut UiCtx<Message>,
+        event: E,
+        state: &mut StateNode,
+        layout: Layout,
+    ) -> EventResponse<E> {
+        for ((child, child_state), child_layout) in
+            self.children.iter_mut().zip(state.children.iter_mut()).zip(layout.clone().children())
+        {
+            match child.on_event(ctx, event.clone(), child_state, child_layout)? {
+                Propagate::Ignored => {},
+                Propagate::BubbleUp(origin, bubbled) => {
+                    let Some(focus_scope) = self.focus_scope else {
+                        return Propagate::BubbleUp(origin, bubbled).into();
+                    };
+
+                    let bounds = |state: &mut StateNode| {
+                        let mut bounds = Vec::new();
+                        self.focus_bounds(state, layout, &mut bounds);
+                        bounds
+                    };
+
+                    return focus_scope.move_focus_cached(ctx, state, bounds, origin, bubbled);
+                },
+            }
+        }
+
+        Propagate::Ignored.into()
+    }
+
+    fn overlay_event(
+        &mut self,
+        ctx: &mut UiCtx<Message>,
+        event: E,
+        state: &mut StateNode,
+        layout: Layout,
+    ) -> Option<EventResponse<E>> {
+        self.children.iter_mut().zip(state.children.iter_mut()).zip(layout.children()).find_map(
+            |((child, child_state), child_layout)| {
+                child.overlay_event(ctx, event.clone(), child_state, child_layout)
+            },
+        )
+    }
+
+    fn perform(
+        &mut self,
+        ctx: &mut UiCtx<Message>,
+        action: &WidgetAction,
+        state: &mut StateNode,
+        layout: Layout,
+    ) -> ActionResult {
+        for ((child, child_state), child_layout) in
+            self.children.iter_mut().zip(state.children.iter_mut()).zip(layout.children())
+        {
+            match child.perform(ctx, action, child_state, child_layout) {
+                ActionResult::Ignored => {},
+                result => return result,
+            }
+        }
+
+        ActionResult::Ignored
+    }
+
+    fn layout(
+        &self,
+        ctx: &mut UiCtx<Message>,
+        state: &mut StateNode,
+        styler: &S,
+        limits: &Limits,
+        viewport: &Viewport,
+    ) -> LayoutNode {
+        if self.focus_scope.is_some() {
+            state.get_mut::<ScopeState>().order = self.tree_ids();
+        }
+
+        Layout::grid(
+            ctx,
+            state,
+            styler,
+            limits,
+            self.size,
+            Position::Relative,
+            viewport,
+            BoxModel::new().padding(self.padding),
+            &self.columns,
+            &self.rows,
+            self.gap,
+            &self.cells,
+            &self.children,
+        )
+    }
+
+    fn draw(
+        &self,
+        ctx: &mut UiCtx<Message>,
+        state: &mut StateNode,
+        renderer: &mut R,
+        styler: &S,
+        layout: Layout,
+        viewport: &Viewport,
+    ) {
+        for ((child, child_state), child_layout) in
+            self.children.iter().zip(state.children.iter_mut()).zip(layout.children())
+        {
+            child.draw(ctx, child_state, renderer, styler, child_layout, viewport);
+        }
+    }
+
+    fn draw_overlay(
+        &self,
+        ctx: &mut UiCtx<Message>,
+        state: &mut StateNode,
+        renderer: &mut R,
+        styler: &S,
+        layout: Layout,
+        viewport: &Viewport,
+    ) {
+        for ((child, child_state), child_layout) in
+            self.children.iter().zip(state.children.iter_mut()).zip(layout.children())
+        {
+            child.draw_overlay(ctx, child_state, renderer, styler, child_layout, viewport);
+        }
+    }
+}
+
+impl<'a, Message, R, E, S, const N: usize> From<Grid<'a, Message, R, E, S, N>>
+    for El<'a, Message, R, E, S>
+where
+    Message: 'a,
+    R: Renderer + 'a,
+    E: Event + 'a,
+    S: 'a,
+{
+    fn from(value: Grid<'a, Message, R, E, S, N>) -> Self {
+        Self::new(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::{string::ToString, vec::Vec};
+    use embedded_graphics::pixelcolor::Rgb888;
+
+    use crate::{
+        align::Align,
+        helpers::{container, grid},
+        icons::IconKind,
+        render::DrawTargetRenderer,
+        size::Length,
+        testing::{harness, snapshot, Event, View},
+        theme::Theme,
+        widgets::icon::Icon,
+    };
+
+    use super::{Cell, Grid};
+
+    type TestGrid = Grid<'static, (), DrawTargetRenderer<Rgb888>, Event, Theme<Rgb888>>;
+
+    #[test]
+    fn pushes_into_free_cells() {
+        let grid = TestGrid::new([Length::Fill, Length::Fill], [Length::Fill; 3])
+            .cell(Cell::new(0, 0).span(1, 2), "tall")
+            .push("a")
+            .push("b")
+            .push("c");
+
+        let places = grid.cells.iter().map(|cell| (cell.column, cell.row)).collect::<Vec<_>>();
+        assert_eq!(places, [(0, 0), (1, 0), (1, 1), (0, 2)]);
+    }
+
+    #[test]
+    fn lays_out_cells() {
+        let view = || -> View {
+            let icon = || Icon::new(IconKind::Cross).size(8);
+            grid([Length::Shrink, Length::Fill, Length::Fixed(12)], [Length::Shrink, Length::Fill])
+                .gap(2)
+                .padding(1)
+                .push(icon())
+                .push("bb")
+                .push("c")
+                .cell(Cell::new(0, 1).span(2, 1).align(Align::Center, Align::End), icon())
+                .cell(Cell::new(2, 1), container(""))
+                .into()
+        };
+        snapshot("grid", view, |h| h);
+
+        let mut harness = harness(view());
+        let dump = harness.ui().dump_tree().to_string();
+        assert_eq!(
+            dump.lines().collect::<Vec<_>>(),
+            [
+                "Grid 0,0 64x32 [fill fill]",
+                "  Icon 1,1 8x8 [8 8]",
+                "  Text 11,1 38x6 [shrink shrink] <TextState> TextState { max_width: 38, height: 6, .. }",
+                "  Text 51,1 12x6 [shrink shrink] <TextState> TextState { max_width: 12, height: 6, .. }",
+                "  Icon 21,23 8x8 [8 8]",
+                "  Container 51,11 12x20 [fill fill]",
+                "    Text 52,12 10x6 [shrink shrink] <TextState> TextState { max_width: 10, height: 6, .. }",
+            ]
+        );
+    }
+}
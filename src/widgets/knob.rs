@@ -1,10 +1,12 @@
 use alloc::{boxed::Box, vec::Vec};
 use embedded_graphics::{
-    geometry::Angle,
+    geometry::{Angle, Point},
     primitives::{Arc, Circle, PrimitiveStyle},
 };
 
 use crate::{
+    acceleration::{Acceleration, Velocity},
+    action::{ActionResult, WidgetAction},
     block::BoxModel,
     el::{El, ElId},
     event::{Capture, CommonEvent, Event, Propagate},
@@ -16,18 +18,23 @@ use crate::{
     style::component_style,
     theme::Theme,
     ui::UiCtx,
-    widget::Widget,
+    widget::{perform_default, Widget},
 };
 
-#[derive(Clone, Copy)]
+pub use crate::event::KnobEvent;
+
+#[derive(Clone, Copy, Debug)]
 struct KnobState {
     active: bool,
     pressed: bool,
+    /// Rotated while pressed, so release doesn't toggle active
+    adjusted: bool,
+    velocity: Velocity,
 }
 
 impl Default for KnobState {
     fn default() -> Self {
-        Self { active: false, pressed: false }
+        Self { active: false, pressed: false, adjusted: false, velocity: Velocity::default() }
     }
 }
 
@@ -72,6 +79,23 @@ pub fn primary<C: PaletteColor>(theme: &Theme<C>, status: KnobStatus) -> KnobSty
 
 pub type KnobValue = u8;
 
+/// Angle of the vector clockwise from 3 o'clock in degrees, as [`Arc`]
+/// angles go. Approximated, the error is below a quarter of a degree.
+fn clockwise_degrees(vector: Point) -> f32 {
+    let (x, y) = (vector.x.abs() as f32, vector.y.abs() as f32);
+    let ratio = x.min(y) / x.max(y);
+
+    let octant = 45.0 * ratio + 15.64 * ratio * (1.0 - ratio);
+    let quadrant = if y > x { 90.0 - octant } else { octant };
+
+    match (vector.x < 0, vector.y < 0) {
+        (false, false) => quadrant,
+        (true, false) => 180.0 - quadrant,
+        (true, true) => 180.0 + quadrant,
+        (false, true) => 360.0 - quadrant,
+    }
+}
+
 pub struct Knob<'a, Message, R, E, S>
 where
     R: Renderer,
@@ -82,6 +106,8 @@ where
     diameter: Length,
     value: KnobValue,
     step: KnobValue,
+    fine_step: KnobValue,
+    acceleration: Option<Acceleration>,
     min: KnobValue,
     max: KnobValue,
     inner: Option<El<'a, Message, R, E, S>>,
@@ -93,7 +119,7 @@ where
 impl<'a, Message, R, E, S> Knob<'a, Message, R, E, S>
 where
     R: Renderer,
-    E: Event,
+    E: KnobEvent,
     S: KnobStyler<R::Color>,
 {
     // pub fn new<F>(on_change: F) -> Self
@@ -123,6 +149,8 @@ where
             diameter: Length::Fill,
             value: 127,
             step: 1,
+            fine_step: 1,
+            acceleration: None,
             min: 0,
             max: KnobValue::MAX,
             inner: None,
@@ -152,6 +180,19 @@ where
         self
     }
 
+    /// Step of rotation while the focus button is held, for fine adjustment
+    /// with coarse `step`
+    pub fn fine_step(mut self, fine_step: KnobValue) -> Self {
+        self.fine_step = fine_step;
+        self
+    }
+
+    /// Speed up fast rotation of the active knob
+    pub fn acceleration(mut self, acceleration: Acceleration) -> Self {
+        self.acceleration = Some(acceleration);
+        self
+    }
+
     pub fn diameter(mut self, diameter: impl Into<Length>) -> Self {
         self.diameter = diameter.into();
         self
@@ -168,8 +209,52 @@ where
     }
 
     // Helpers //
+    fn rotate(&mut self, ctx: &mut UiCtx<Message>, steps: i32, step: KnobValue, layout: Layout) {
+        let prev_value = self.value;
+
+        self.value = (prev_value as i32)
+            .saturating_add(steps.saturating_mul(step as i32))
+            .clamp(self.min as i32, self.max as i32) as u8;
+
+        if prev_value != self.value {
+            ctx.mark_dirty(layout.bounds());
+
+            if let Some(on_change) = self.on_change.as_ref() {
+                ctx.publish((on_change)(self.value));
+            }
+        }
+    }
+
+    /// Set value by the pointer angle around the center, the track starts at
+    /// `start` and goes clockwise
+    fn drag_to(&mut self, ctx: &mut UiCtx<Message>, point: Point, layout: Layout) {
+        let bounds = layout.bounds();
+        let offset = point - bounds.center();
+
+        if offset == Point::zero() {
+            return;
+        }
+
+        let mut degrees = (clockwise_degrees(offset) - self.start.to_degrees()) % 360.0;
+        if degrees < 0.0 {
+            degrees += 360.0;
+        }
+
+        let value = ((degrees / 360.0 * u8::MAX as f32) as i32)
+            .clamp(self.min as i32, self.max as i32) as u8;
+
+        if value != self.value {
+            self.value = value;
+            ctx.mark_dirty(bounds);
+
+            if let Some(on_change) = self.on_change.as_ref() {
+                ctx.publish((on_change)(self.value));
+            }
+        }
+    }
+
     fn status(&self, ctx: &UiCtx<Message>, state: &KnobState) -> KnobStatus {
-        let &KnobState { active, pressed } = state;
+        let &KnobState { active, pressed, .. } = state;
         let focused = ctx.is_focused(self);
 
         KnobStatus { focused, active, pressed }
@@ -179,7 +264,7 @@ where
 impl<'a, Message, R, E, S> Widget<Message, R, E, S> for Knob<'a, Message, R, E, S>
 where
     R: Renderer,
-    E: Event,
+    E: KnobEvent,
     S: KnobStyler<R::Color>,
 {
     fn id(&self) -> Option<ElId> {
@@ -206,58 +291,106 @@ where
         vec![]
     }
 
+    fn perform(
+        &mut self,
+        ctx: &mut UiCtx<Message>,
+        action: &WidgetAction,
+        state: &mut crate::state::StateNode,
+        layout: Layout,
+    ) -> ActionResult {
+        match *action {
+            WidgetAction::Activate(id) | WidgetAction::Deactivate(id) if id == self.id => {
+                state.get_mut::<KnobState>().active = matches!(action, WidgetAction::Activate(_));
+                ctx.mark_dirty(layout.bounds());
+                ActionResult::Done
+            },
+            _ => perform_default(self, ctx, action, state, layout),
+        }
+    }
+
     fn on_event(
         &mut self,
         ctx: &mut UiCtx<Message>,
         event: E,
         state: &mut crate::state::StateNode,
-        _layout: Layout,
+        layout: Layout,
     ) -> crate::event::EventResponse<E> {
         let focused = ctx.is_focused::<R, E, S>(self);
         let current_state = *state.get::<KnobState>();
 
         if let Some(offset) = event.as_knob_rotation() {
-            if current_state.active {
-                let prev_value = self.value;
-
-                self.value = (prev_value as i32)
-                    .saturating_add(offset * self.step as i32)
-                    .clamp(self.min as i32, self.max as i32) as u8;
-
-                if let Some(on_change) = self.on_change.as_ref() {
-                    if prev_value != self.value {
-                        ctx.publish((on_change)(self.value));
-                    }
-                }
+            // Rotation while pressed is fine adjustment
+            if current_state.pressed {
+                state.get_mut::<KnobState>().adjusted = true;
+                self.rotate(ctx, offset, self.fine_step, layout);
+                return Capture::Captured.into();
+            }
 
+            if current_state.active {
+                let offset = state.get_mut::<KnobState>().velocity.apply(
+                    self.acceleration,
+                    offset,
+                    ctx.now(),
+                );
+                self.rotate(ctx, offset, self.step, layout);
                 return Capture::Captured.into();
             }
         }
 
         if let Some(common) = event.as_common() {
             match common {
-                CommonEvent::FocusMove(_) if focused => {
+                CommonEvent::FocusMove(_) | CommonEvent::FocusDirection(_) if focused => {
                     return Propagate::BubbleUp(self.id, event).into()
                 },
                 CommonEvent::FocusButtonDown if focused => {
-                    state.get_mut::<KnobState>().pressed = true;
+                    let state = state.get_mut::<KnobState>();
+                    state.pressed = true;
+                    state.adjusted = false;
+                    ctx.mark_dirty(layout.bounds());
                     return Capture::Captured.into();
                 },
                 CommonEvent::FocusButtonUp if focused => {
                     state.get_mut::<KnobState>().pressed = false;
 
                     if current_state.pressed {
-                        state.get_mut::<KnobState>().active = !state.get::<KnobState>().active;
+                        ctx.mark_dirty(layout.bounds());
+                        if !current_state.adjusted {
+                            state.get_mut::<KnobState>().active = !current_state.active;
+                        }
 
                         return Capture::Captured.into();
                     }
                 },
+                CommonEvent::PointerDown(point) if focused && layout.bounds().contains(point) => {
+                    state.get_mut::<KnobState>().pressed = true;
+                    self.drag_to(ctx, point, layout);
+                    return Capture::Captured.into();
+                },
+                CommonEvent::PointerMove(point) if current_state.pressed => {
+                    self.drag_to(ctx, point, layout);
+                    return Capture::Captured.into();
+                },
+                CommonEvent::PointerUp(_) if current_state.pressed => {
+                    state.get_mut::<KnobState>().pressed = false;
+                    ctx.mark_dirty(layout.bounds());
+                    return Capture::Captured.into();
+                },
+                CommonEvent::PointerMove(_)
+                | CommonEvent::PointerUp(_)
+                | CommonEvent::Gesture(_)
+                | CommonEvent::ToggleDebug => {},
                 CommonEvent::FocusButtonDown
                 | CommonEvent::FocusButtonUp
                 | CommonEvent::FocusMove(_)
-                | CommonEvent::Exit => {
+                | CommonEvent::FocusDirection(_)
+                | CommonEvent::PointerDown(_)
+                | CommonEvent::Exit
+                | CommonEvent::Back => {
                     // Should we reset state on any event? Or only on common
-                    state.reset::<KnobState>();
+                    if current_state.pressed || current_state.active {
+                        state.reset::<KnobState>();
+                        ctx.mark_dirty(layout.bounds());
+                    }
                 },
             }
         }
@@ -355,10 +488,73 @@ impl<'a, Message, R, E, S> From<Knob<'a, Message, R, E, S>> for El<'a, Message,
 where
     Message: Clone + 'a,
     R: Renderer + 'a,
-    E: Event + 'a,
+    E: KnobEvent + 'a,
     S: KnobStyler<R::Color> + 'a,
 {
     fn from(value: Knob<'a, Message, R, E, S>) -> Self {
         El::new(value)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use core::time::Duration;
+
+    use embedded_graphics::geometry::Point;
+
+    use crate::{
+        acceleration::Acceleration,
+        event::CommonEvent,
+        helpers::knob,
+        testing::{click, harness, Event},
+    };
+
+    fn rotate(steps: usize) -> impl Iterator<Item = Event> {
+        core::iter::repeat_n(CommonEvent::FocusMove(1).into(), steps)
+    }
+
+    #[test]
+    fn follows_pointer_around() {
+        // Knob starts at the top, pointer moves far from the center
+        // to keep the angles exact
+        let mut script = harness(knob(|value| value)).events([
+            CommonEvent::PointerDown(Point::new(32, 16)).into(),
+            CommonEvent::PointerMove(Point::new(1000, 16)).into(),
+            CommonEvent::PointerMove(Point::new(32, 1000)).into(),
+            CommonEvent::PointerMove(Point::new(-1000, 16)).into(),
+            CommonEvent::PointerMove(Point::new(32, -1000)).into(),
+            CommonEvent::PointerUp(Point::new(32, -1000)).into(),
+        ]);
+
+        // Press jumps to the pointer, then quarter turns go clockwise,
+        // wrapping to the minimum at the top
+        assert_eq!(script.messages()[1..], [63, 127, 191, 0]);
+    }
+
+    #[test]
+    fn rotates_by_fine_step_while_pressed() {
+        let mut script = harness(knob(|value| value).step(10).fine_step(1))
+            .auto_focus()
+            .events([CommonEvent::FocusButtonDown.into()])
+            .events(rotate(2))
+            .events([CommonEvent::FocusButtonUp.into()]);
+        assert_eq!(script.messages(), [128, 129]);
+
+        // Adjusting release doesn't activate the knob
+        let mut script = script.events(rotate(1));
+        assert_eq!(script.messages(), []);
+    }
+
+    #[test]
+    fn accelerates_fast_rotation() {
+        let acceleration = Acceleration::new(Duration::from_millis(50), 3);
+        let mut script = harness(knob(|value| value).step(10).acceleration(acceleration))
+            .auto_focus()
+            .events(click())
+            .events(rotate(4));
+        assert_eq!(script.messages(), [137, 157, 187, 217]);
+
+        let mut script = script.advance(Duration::from_millis(50)).events(rotate(1));
+        assert_eq!(script.messages(), [227]);
+    }
+}
@@ -1,18 +1,23 @@
 use core::marker::PhantomData;
 
 use alloc::vec::Vec;
+use embedded_graphics::primitives::Rectangle;
 
 use crate::{
+    action::{ActionResult, WidgetAction},
     align::Align,
     axis::Axis,
     block::BoxModel,
+    collections::{self, CapacityError, Children, DEFAULT_CAPACITY},
+    debug::WidgetDebug,
     el::{El, ElId},
     event::{Event, EventResponse, Propagate},
+    focus::{FocusScope, ScopeState},
     layout::{Layout, Viewport},
     padding::Padding,
     render::Renderer,
     size::{Length, Size},
-    state::StateNode,
+    state::{State, StateNode, StateTag},
     ui::UiCtx,
     widget::Widget,
 };
@@ -31,29 +36,55 @@ impl LinearDirection for DirectionRow {
     const AXIS: Axis = Axis::X;
 }
 
-pub type Column<'a, Message, R, E, S> = Linear<'a, Message, R, E, S, DirectionColumn>;
-pub type Row<'a, Message, R, E, S> = Linear<'a, Message, R, E, S, DirectionRow>;
+pub type Column<'a, Message, R, E, S, const N: usize = DEFAULT_CAPACITY> =
+    Linear<'a, Message, R, E, S, DirectionColumn, N>;
+pub type Row<'a, Message, R, E, S, const N: usize = DEFAULT_CAPACITY> =
+    Linear<'a, Message, R, E, S, DirectionRow, N>;
 
-pub struct Linear<'a, Message, R: Renderer, E: Event, S, D: LinearDirection> {
+/// Lays out children along an axis. With `heapless` feature `N` is the
+/// maximum count of children, see [`collections`].
+pub struct Linear<
+    'a,
+    Message,
+    R: Renderer,
+    E: Event,
+    S,
+    D: LinearDirection,
+    const N: usize = DEFAULT_CAPACITY,
+> {
     spacing: u32,
     size: Size<Length>,
     padding: Padding,
     gap: u32,
     align: Align,
-    children: Vec<El<'a, Message, R, E, S>>,
+    children: Children<El<'a, Message, R, E, S>, N>,
+    focus_scope: Option<FocusScope>,
 
     dir: PhantomData<D>,
 }
 
 impl<'a, Message, R: Renderer, E: Event, S, D: LinearDirection> Linear<'a, Message, R, E, S, D> {
+    /// Linear fitting [`DEFAULT_CAPACITY`] children, see
+    /// [`Linear::with_capacity`]
     pub fn new(children: impl IntoIterator<Item = El<'a, Message, R, E, S>>) -> Self {
+        Self::with_capacity(children)
+    }
+}
+
+impl<'a, Message, R: Renderer, E: Event, S, D: LinearDirection, const N: usize>
+    Linear<'a, Message, R, E, S, D, N>
+{
+    /// Linear fitting `N` children. Panics if children don't fit, prefer
+    /// [`Linear::try_add`] with `heapless` feature, see [`collections`].
+    pub fn with_capacity(children: impl IntoIterator<Item = El<'a, Message, R, E, S>>) -> Self {
         Self {
             spacing: 0,
             size: Size::fill(),
             padding: Padding::default(),
             gap: 0,
             align: Align::Start,
-            children: children.into_iter().collect(),
+            children: collections::collect_or_overflow::<_, N>(children),
+            focus_scope: None,
             dir: PhantomData,
         }
     }
@@ -88,11 +119,31 @@ impl<'a, Message, R: Renderer, E: Event, S, D: LinearDirection> Linear<'a, Messa
         self
     }
 
+    /// Make linear a focus scope, so focus moves between its children
+    /// first, see [`FocusScope`]
+    pub fn focus_scope(mut self, focus_scope: FocusScope) -> Self {
+        self.focus_scope = Some(focus_scope);
+        self
+    }
+
+    /// Add child. Panics if linear is full, prefer [`Linear::try_add`]
+    /// with `heapless` feature, see [`collections`].
     pub fn add(mut self, child: impl Into<El<'a, Message, R, E, S>>) -> Self {
-        self.children.push(child.into());
+        collections::push_or_overflow::<_, N>(&mut self.children, child.into());
         self
     }
 
+    /// Add child, failing if linear is full
+    pub fn try_add(
+        mut self,
+        child: impl Into<El<'a, Message, R, E, S>>,
+    ) -> Result<Self, CapacityError> {
+        match collections::push::<_, N>(&mut self.children, child.into()) {
+            Ok(()) => Ok(self),
+            Err(_) => Err(CapacityError { capacity: N }),
+        }
+    }
+
     // fn focus_child(&self, child_index: usize, focus_offset: i32) -> FocusResult {
     //     let new_focus_index = child_index as i32 + focus_offset;
 
@@ -112,8 +163,8 @@ impl<'a, Message, R: Renderer, E: Event, S, D: LinearDirection> Linear<'a, Messa
     // }
 }
 
-impl<'a, Message, R: Renderer, E: Event, S, D: LinearDirection> Widget<Message, R, E, S>
-    for Linear<'a, Message, R, E, S, D>
+impl<'a, Message, R: Renderer, E: Event, S, D: LinearDirection, const N: usize>
+    Widget<Message, R, E, S> for Linear<'a, Message, R, E, S, D, N>
 {
     fn id(&self) -> Option<crate::el::ElId> {
         None
@@ -123,10 +174,54 @@ impl<'a, Message, R: Renderer, E: Event, S, D: LinearDirection> Widget<Message,
         self.children.iter().map(|child| child.tree_ids()).flatten().collect()
     }
 
+    fn focus_bounds(
+        &self,
+        state: &mut StateNode,
+        layout: Layout,
+        bounds: &mut Vec<(ElId, Rectangle)>,
+    ) {
+        for ((child, child_state), child_layout) in
+            self.children.iter().zip(state.children.iter_mut()).zip(layout.children())
+        {
+            child.focus_bounds(child_state, child_layout, bounds);
+        }
+    }
+
+    fn debug(
+        &self,
+        state: &mut StateNode,
+        layout: Layout,
+        viewport: &Viewport,
+    ) -> Option<WidgetDebug<R::Color>> {
+        let debug = WidgetDebug::new(self, state, layout.clone(), viewport);
+        let children =
+            self.children.iter().zip(state.children.iter_mut()).zip(layout.children()).map(
+                |((child, child_state), child_layout)| {
+                    child.debug(child_state, child_layout, viewport)
+                },
+            );
+
+        Some(debug.children(children))
+    }
+
     fn size(&self, _viewport: &Viewport) -> crate::size::Size<Length> {
         self.size
     }
 
+    fn state_tag(&self) -> StateTag {
+        match self.focus_scope {
+            Some(_) => StateTag::of::<ScopeState>(),
+            None => StateTag::stateless(),
+        }
+    }
+
+    fn state(&self) -> State {
+        match self.focus_scope {
+            Some(_) => State::new(ScopeState::default()),
+            None => State::None,
+        }
+    }
+
     fn state_children(&self) -> Vec<StateNode> {
         self.children.iter().map(|child| StateNode::new(child)).collect()
     }
@@ -139,17 +234,62 @@ impl<'a, Message, R: Renderer, E: Event, S, D: LinearDirection> Widget<Message,
         layout: Layout,
     ) -> EventResponse<E> {
         for ((child, child_state), child_layout) in
-            self.children.iter_mut().zip(state.children.iter_mut()).zip(layout.children())
+            self.children.iter_mut().zip(state.children.iter_mut()).zip(layout.clone().children())
         {
             match child.on_event(ctx, event.clone(), child_state, child_layout)? {
                 Propagate::Ignored => {},
-                bubbled @ Propagate::BubbleUp(..) => return bubbled.into(),
+                Propagate::BubbleUp(origin, bubbled) => {
+                    let Some(focus_scope) = self.focus_scope else {
+                        return Propagate::BubbleUp(origin, bubbled).into();
+                    };
+
+                    let bounds = |state: &mut StateNode| {
+                        let mut bounds = Vec::new();
+                        self.focus_bounds(state, layout, &mut bounds);
+                        bounds
+                    };
+
+                    return focus_scope.move_focus_cached(ctx, state, bounds, origin, bubbled);
+                },
             }
         }
 
         Propagate::Ignored.into()
     }
 
+    fn overlay_event(
+        &mut self,
+        ctx: &mut UiCtx<Message>,
+        event: E,
+        state: &mut StateNode,
+        layout: Layout,
+    ) -> Option<EventResponse<E>> {
+        self.children.iter_mut().zip(state.children.iter_mut()).zip(layout.children()).find_map(
+            |((child, child_state), child_layout)| {
+                child.overlay_event(ctx, event.clone(), child_state, child_layout)
+            },
+        )
+    }
+
+    fn perform(
+        &mut self,
+        ctx: &mut UiCtx<Message>,
+        action: &WidgetAction,
+        state: &mut StateNode,
+        layout: Layout,
+    ) -> ActionResult {
+        for ((child, child_state), child_layout) in
+            self.children.iter_mut().zip(state.children.iter_mut()).zip(layout.children())
+        {
+            match child.perform(ctx, action, child_state, child_layout) {
+                ActionResult::Ignored => {},
+                result => return result,
+            }
+        }
+
+        ActionResult::Ignored
+    }
+
     fn layout(
         &self,
         ctx: &mut UiCtx<Message>,
@@ -158,6 +298,10 @@ impl<'a, Message, R: Renderer, E: Event, S, D: LinearDirection> Widget<Message,
         limits: &crate::layout::Limits,
         viewport: &Viewport,
     ) -> crate::layout::LayoutNode {
+        if self.focus_scope.is_some() {
+            state.get_mut::<ScopeState>().order = self.tree_ids();
+        }
+
         Layout::flex(
             ctx,
             state,
@@ -190,9 +334,26 @@ impl<'a, Message, R: Renderer, E: Event, S, D: LinearDirection> Widget<Message,
             child.draw(ctx, child_state, renderer, styler, child_layout, viewport);
         }
     }
+
+    fn draw_overlay(
+        &self,
+        ctx: &mut UiCtx<Message>,
+        state: &mut StateNode,
+        renderer: &mut R,
+        styler: &S,
+        layout: Layout,
+        viewport: &Viewport,
+    ) {
+        for ((child, child_state), child_layout) in
+            self.children.iter().zip(state.children.iter_mut()).zip(layout.children())
+        {
+            child.draw_overlay(ctx, child_state, renderer, styler, child_layout, viewport);
+        }
+    }
 }
 
-impl<'a, Message, R, E, S, D> From<Linear<'a, Message, R, E, S, D>> for El<'a, Message, R, E, S>
+impl<'a, Message, R, E, S, D, const N: usize> From<Linear<'a, Message, R, E, S, D, N>>
+    for El<'a, Message, R, E, S>
 where
     Message: 'a,
     R: Renderer + 'a,
@@ -200,7 +361,89 @@ where
     S: 'a,
     D: LinearDirection + 'a,
 {
-    fn from(value: Linear<'a, Message, R, E, S, D>) -> Self {
+    fn from(value: Linear<'a, Message, R, E, S, D, N>) -> Self {
         Self::new(value)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        col,
+        event::CommonEvent,
+        focus::{Direction, FocusScope},
+        helpers::{button, container},
+        row,
+        testing::{click, harness, View},
+    };
+
+    fn numbered(number: u8) -> View<u8> {
+        button("").on_press(number).into()
+    }
+
+    /// Number of the button focused after `moves` from the first one
+    fn focused_after(
+        view: impl Into<View<u8>>,
+        moves: impl IntoIterator<Item = CommonEvent>,
+    ) -> u8 {
+        let mut script =
+            harness(view).auto_focus().events(moves.into_iter().map(Into::into)).events(click());
+        script.messages()[0]
+    }
+
+    fn forward(count: usize) -> impl Iterator<Item = CommonEvent> {
+        core::iter::repeat_n(CommonEvent::FocusMove(1), count)
+    }
+
+    #[test]
+    fn scope_ends_in_nested_linear() {
+        let view = |scope| {
+            col![numbered(1), row![numbered(2), numbered(3)].focus_scope(scope), numbered(4)]
+        };
+        let back = || forward(1).chain([CommonEvent::FocusMove(-1)]);
+
+        // Pass hands the move to the root order on both ends
+        assert_eq!(focused_after(view(FocusScope::Pass), forward(3)), 4);
+        assert_eq!(focused_after(view(FocusScope::Pass), back()), 1);
+
+        assert_eq!(focused_after(view(FocusScope::Wrap), forward(3)), 2);
+        assert_eq!(focused_after(view(FocusScope::Wrap), back()), 3);
+
+        assert_eq!(focused_after(view(FocusScope::Trap), forward(3)), 3);
+        assert_eq!(focused_after(view(FocusScope::Trap), back()), 2);
+    }
+
+    #[test]
+    fn scope_ends_in_container() {
+        let view = |scope| {
+            col![
+                numbered(1),
+                container(row![numbered(2), numbered(3)]).focus_scope(scope),
+                numbered(4)
+            ]
+        };
+        let down = || forward(2).chain([CommonEvent::FocusDirection(Direction::Down)]);
+
+        assert_eq!(focused_after(view(FocusScope::Pass), forward(3)), 4);
+        assert_eq!(focused_after(view(FocusScope::Pass), down()), 4);
+
+        // Directional moves stop at the edge of wrapping scope
+        assert_eq!(focused_after(view(FocusScope::Wrap), forward(3)), 2);
+        assert_eq!(focused_after(view(FocusScope::Wrap), down()), 3);
+
+        assert_eq!(focused_after(view(FocusScope::Trap), forward(3)), 3);
+        assert_eq!(focused_after(view(FocusScope::Trap), down()), 3);
+    }
+
+    #[test]
+    fn passing_scope_moves_in_outer_scope() {
+        let view = || {
+            let inner = row![numbered(2), numbered(3)].focus_scope(FocusScope::Pass);
+            col![numbered(1), row![inner, numbered(4)].focus_scope(FocusScope::Wrap), numbered(5)]
+        };
+
+        // Passed by the inner scope, wrapped by the outer one before the root
+        assert_eq!(focused_after(view(), forward(3)), 4);
+        assert_eq!(focused_after(view(), forward(4)), 2);
+    }
+}
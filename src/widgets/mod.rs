@@ -6,9 +6,12 @@ pub mod focus_input;
 pub mod icon;
 pub mod knob;
 pub mod linear;
+pub mod overlay;
+pub mod router;
 pub mod select;
 pub mod slider;
 pub mod text;
 pub mod virtual_keyboard;
 pub mod container;
 pub mod scrollable;
+pub mod grid;
@@ -0,0 +1,384 @@
+use alloc::vec::Vec;
+use embedded_graphics::primitives::Rectangle;
+
+use crate::{
+    action::{ActionResult, WidgetAction},
+    align::Align,
+    debug::WidgetDebug,
+    el::{El, ElId},
+    event::{Event, EventResponse},
+    layout::{Layout, LayoutNode, Limits, Viewport},
+    padding::Padding,
+    render::Renderer,
+    size::{Length, Size},
+    state::StateNode,
+    ui::UiCtx,
+    widget::Widget,
+};
+
+/// Where overlay content is placed
+#[derive(Clone, Copy)]
+pub enum Placement {
+    /// Aligned in viewport, e.g. centered dialog or toast at the bottom
+    Viewport(Align, Align),
+    /// Right under the base element, e.g. dropdown
+    Below,
+}
+
+/// Element with content shown above the whole tree while open, e.g. popup,
+/// dropdown or dialog. Overlay content receives events before the tree,
+/// modal overlay receives all of them and traps focus.
+pub struct Overlay<'a, Message, R: Renderer, E: Event, S> {
+    base: El<'a, Message, R, E, S>,
+    content: El<'a, Message, R, E, S>,
+    open: bool,
+    modal: bool,
+    placement: Placement,
+}
+
+impl<'a, Message, R: Renderer, E: Event, S> Overlay<'a, Message, R, E, S> {
+    pub fn new(
+        base: impl Into<El<'a, Message, R, E, S>>,
+        content: impl Into<El<'a, Message, R, E, S>>,
+    ) -> Self {
+        Self {
+            base: base.into(),
+            content: content.into(),
+            open: true,
+            modal: false,
+            placement: Placement::Viewport(Align::Center, Align::Center),
+        }
+    }
+
+    pub fn open(mut self, open: bool) -> Self {
+        self.open = open;
+        self
+    }
+
+    /// Don't let events and focus to the rest of the UI while open
+    pub fn modal(mut self) -> Self {
+        self.modal = true;
+        self
+    }
+
+    pub fn placement(mut self, placement: Placement) -> Self {
+        self.placement = placement;
+        self
+    }
+
+    pub fn below(self) -> Self {
+        self.placement(Placement::Below)
+    }
+
+    pub fn align(self, horizontal: Align, vertical: Align) -> Self {
+        self.placement(Placement::Viewport(horizontal, vertical))
+    }
+}
+
+impl<'a, Message, R: Renderer, E: Event, S> Widget<Message, R, E, S>
+    for Overlay<'a, Message, R, E, S>
+{
+    fn id(&self) -> Option<ElId> {
+        None
+    }
+
+    fn tree_ids(&self) -> Vec<ElId> {
+        let mut ids = self.base.tree_ids();
+        if self.open {
+            ids.extend(self.content.tree_ids());
+        }
+        ids
+    }
+
+    fn focus_bounds(
+        &self,
+        state: &mut StateNode,
+        layout: Layout,
+        bounds: &mut Vec<(ElId, Rectangle)>,
+    ) {
+        let mut children = layout.children();
+
+        self.base.focus_bounds(&mut state.children[0], children.next().unwrap(), bounds);
+        if let Some(content_layout) = children.next() {
+            self.content.focus_bounds(&mut state.children[1], content_layout, bounds);
+        }
+    }
+
+    fn debug(
+        &self,
+        state: &mut StateNode,
+        layout: Layout,
+        viewport: &Viewport,
+    ) -> Option<WidgetDebug<R::Color>> {
+        let debug = WidgetDebug::new(self, state, layout.clone(), viewport);
+        let mut children = layout.children();
+
+        let base = self.base.debug(&mut state.children[0], children.next().unwrap(), viewport);
+        let content = children.next().and_then(|content_layout| {
+            self.content.debug(&mut state.children[1], content_layout, viewport)
+        });
+
+        Some(debug.children([base, content]))
+    }
+
+    fn size(&self, viewport: &Viewport) -> Size<Length> {
+        self.base.size(viewport)
+    }
+
+    fn state_children(&self) -> Vec<StateNode> {
+        vec![StateNode::new(&self.base), StateNode::new(&self.content)]
+    }
+
+    fn layout(
+        &self,
+        ctx: &mut UiCtx<Message>,
+        state: &mut StateNode,
+        styler: &S,
+        limits: &Limits,
+        viewport: &Viewport,
+    ) -> LayoutNode {
+        let base = self.base.layout(ctx, &mut state.children[0], styler, limits, viewport);
+        let size = base.size();
+
+        if !self.open {
+            return LayoutNode::with_children(size, Padding::default(), [base]);
+        }
+
+        let content = self.content.layout(
+            ctx,
+            &mut state.children[1],
+            styler,
+            &Limits::only_max(viewport.size),
+            viewport,
+        );
+        let content = match self.placement {
+            Placement::Viewport(horizontal, vertical) => {
+                content.aligned(horizontal, vertical, viewport.size).absolute()
+            },
+            Placement::Below => content.moved((0, size.height as i32)),
+        };
+
+        if self.modal {
+            ctx.trap_focus(self.content.tree_ids());
+        }
+
+        LayoutNode::with_children(size, Padding::default(), [base, content])
+    }
+
+    fn draw(
+        &self,
+        ctx: &mut UiCtx<Message>,
+        state: &mut StateNode,
+        renderer: &mut R,
+        styler: &S,
+        layout: Layout,
+        viewport: &Viewport,
+    ) {
+        self.base.draw(
+            ctx,
+            &mut state.children[0],
+            renderer,
+            styler,
+            layout.first_child(),
+            viewport,
+        )
+    }
+
+    fn draw_overlay(
+        &self,
+        ctx: &mut UiCtx<Message>,
+        state: &mut StateNode,
+        renderer: &mut R,
+        styler: &S,
+        layout: Layout,
+        viewport: &Viewport,
+    ) {
+        let mut children = layout.children();
+        let base_layout = children.next().unwrap();
+
+        self.base.draw_overlay(
+            ctx,
+            &mut state.children[0],
+            renderer,
+            styler,
+            base_layout,
+            viewport,
+        );
+
+        if let Some(content_layout) = children.next() {
+            let content_state = &mut state.children[1];
+
+            self.content.draw(
+                ctx,
+                content_state,
+                renderer,
+                styler,
+                content_layout.clone(),
+                viewport,
+            );
+            self.content.draw_overlay(
+                ctx,
+                content_state,
+                renderer,
+                styler,
+                content_layout,
+                viewport,
+            );
+        }
+    }
+
+    fn on_event(
+        &mut self,
+        ctx: &mut UiCtx<Message>,
+        event: E,
+        state: &mut StateNode,
+        layout: Layout,
+    ) -> EventResponse<E> {
+        self.base.on_event(ctx, event, &mut state.children[0], layout.first_child())
+    }
+
+    fn overlay_event(
+        &mut self,
+        ctx: &mut UiCtx<Message>,
+        event: E,
+        state: &mut StateNode,
+        layout: Layout,
+    ) -> Option<EventResponse<E>> {
+        let mut children = layout.children();
+        let base_layout = children.next().unwrap();
+
+        let Some(content_layout) = children.next() else {
+            return self.base.overlay_event(ctx, event, &mut state.children[0], base_layout);
+        };
+
+        let content_state = &mut state.children[1];
+
+        Some(
+            self.content
+                .overlay_event(ctx, event.clone(), content_state, content_layout.clone())
+                .unwrap_or_else(|| {
+                    self.content.on_event(ctx, event, content_state, content_layout)
+                }),
+        )
+    }
+
+    fn perform(
+        &mut self,
+        ctx: &mut UiCtx<Message>,
+        action: &WidgetAction,
+        state: &mut StateNode,
+        layout: Layout,
+    ) -> ActionResult {
+        let mut children = layout.children();
+        let base_layout = children.next().unwrap();
+
+        if let Some(content_layout) = children.next() {
+            match self.content.perform(ctx, action, &mut state.children[1], content_layout) {
+                ActionResult::Ignored => {},
+                result => return result,
+            }
+        }
+
+        self.base.perform(ctx, action, &mut state.children[0], base_layout)
+    }
+}
+
+impl<'a, Message, R, E, S> From<Overlay<'a, Message, R, E, S>> for El<'a, Message, R, E, S>
+where
+    Message: 'a,
+    R: Renderer + 'a,
+    E: Event + 'a,
+    S: 'a,
+{
+    fn from(value: Overlay<'a, Message, R, E, S>) -> Self {
+        Self::new(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use embedded_graphics::geometry::Point;
+
+    use crate::{
+        align::Align,
+        col,
+        helpers::{button, overlay, scrollable_v, text},
+        size::Length,
+        testing::{click, harness, tap, View},
+    };
+
+    fn small_button(label: &'static str, on_press: u8) -> View<u8> {
+        button(label).width(Length::Shrink).height(Length::Shrink).on_press(on_press).into()
+    }
+
+    /// Base button at the top left, overlay button at the bottom right
+    fn view(open: bool, modal: bool) -> View<u8> {
+        let popup = overlay(text("Base"), small_button("Top", 2)).open(open);
+        let popup = if modal { popup.modal() } else { popup };
+
+        col![small_button("Base", 1), popup.align(Align::End, Align::End)].into()
+    }
+
+    #[test]
+    fn draws_content_only_while_open() {
+        let closed = harness(view(false, false));
+        let open = harness(view(true, false));
+        assert_ne!(closed.frame().pixels(), open.frame().pixels());
+
+        let base = harness(col![small_button("Base", 1), text("Base")]);
+        assert_eq!(closed.frame().pixels(), base.frame().pixels());
+    }
+
+    #[test]
+    fn closed_content_gets_no_events() {
+        let mut ui = harness(view(false, false)).events(tap(Point::new(62, 30)));
+        assert_eq!(ui.messages(), []);
+
+        let mut ui = ui.events(tap(Point::new(2, 2)));
+        assert_eq!(ui.messages(), [1]);
+    }
+
+    #[test]
+    fn content_captures_events_before_tree() {
+        let mut ui = harness(view(true, false)).events(tap(Point::new(62, 30)));
+        assert_eq!(ui.messages(), [2]);
+
+        // Focused content captures focus button too
+        let mut ui = ui.events(click());
+        assert_eq!(ui.messages(), [2]);
+    }
+
+    #[test]
+    fn outside_click_passes_to_tree_unless_modal() {
+        let mut ui = harness(view(true, false)).events(tap(Point::new(2, 2)));
+        assert_eq!(ui.messages(), [1]);
+
+        let mut ui = harness(view(true, true)).events(tap(Point::new(2, 2)));
+        assert_eq!(ui.messages(), []);
+
+        // Modal traps focus in its content
+        let mut ui = ui.events(click());
+        assert_eq!(ui.messages(), [2]);
+    }
+
+    #[test]
+    fn containers_pass_overlays_on() {
+        let wrapped = |open: bool| -> [View<u8>; 2] {
+            let popup = || {
+                overlay(text("Base"), small_button("Top", 2))
+                    .open(open)
+                    .modal()
+                    .align(Align::End, Align::End)
+            };
+            [button(popup()).into(), scrollable_v(popup()).into()]
+        };
+
+        for (open, closed) in wrapped(true).into_iter().zip(wrapped(false)) {
+            let open = harness(open);
+            assert_ne!(open.frame().pixels(), harness(closed).frame().pixels());
+
+            let mut open = open.events(tap(Point::new(62, 30)));
+            assert_eq!(open.messages(), [2]);
+        }
+    }
+}
@@ -0,0 +1,487 @@
+use core::cell::Cell;
+
+use alloc::vec::Vec;
+use embedded_graphics::primitives::Rectangle;
+
+use crate::{
+    action::{ActionResult, Navigation, WidgetAction},
+    collections::{self, CapacityError, Children, DEFAULT_CAPACITY},
+    debug::WidgetDebug,
+    el::{El, ElId},
+    event::{Capture, CommonEvent, Event, EventResponse, Propagate},
+    layout::{Layout, LayoutNode, Limits, Viewport},
+    padding::Padding,
+    render::Renderer,
+    size::{Length, Size},
+    state::{State, StateKey, StateNode, StateTag},
+    ui::UiCtx,
+    widget::{perform_default, Widget},
+};
+
+/// Name of a [`Router`] page
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(::defmt::Format))]
+pub struct PageId(&'static str);
+
+impl PageId {
+    pub const fn new(name: &'static str) -> Self {
+        Self(name)
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.0
+    }
+}
+
+impl From<&'static str> for PageId {
+    fn from(value: &'static str) -> Self {
+        Self::new(value)
+    }
+}
+
+#[derive(Debug, Default)]
+struct RouterState {
+    /// Opened pages with the element focused when page was left, the last
+    /// one is shown. Empty until the first navigation, the first page is
+    /// shown then.
+    stack: Vec<(PageId, Option<ElId>)>,
+}
+
+/// Shows one of its pages at a time, keeping states of all of them, so
+/// focus and scroll survive coming back to a page. Pages are changed by
+/// [`Navigation`] actions, [`CommonEvent::Back`] goes to the previous page.
+/// With `heapless` feature `N` is the maximum count of pages, see
+/// [`collections`].
+pub struct Router<'a, Message, R: Renderer, E: Event, S, const N: usize = DEFAULT_CAPACITY> {
+    pages: Children<(PageId, El<'a, Message, R, E, S>), N>,
+    /// Index of the shown page, synced with state on layout and navigation,
+    /// as `tree_ids` and `size` have no access to state
+    current: Cell<usize>,
+}
+
+impl<'a, Message, R: Renderer, E: Event, S> Router<'a, Message, R, E, S> {
+    pub fn new() -> Self {
+        Self::with_capacity()
+    }
+}
+
+impl<'a, Message, R: Renderer, E: Event, S, const N: usize> Router<'a, Message, R, E, S, N> {
+    /// Router fitting `N` pages
+    pub fn with_capacity() -> Self {
+        Self { pages: Children::new(), current: Cell::new(0) }
+    }
+
+    /// Add page, the first one is shown initially. Panics if router is
+    /// full, prefer [`Router::try_page`] with `heapless` feature, see
+    /// [`collections`].
+    pub fn page(
+        mut self,
+        id: impl Into<PageId>,
+        page: impl Into<El<'a, Message, R, E, S>>,
+    ) -> Self {
+        collections::push_or_overflow::<_, N>(&mut self.pages, (id.into(), page.into()));
+        self
+    }
+
+    /// Add page, failing if router is full
+    pub fn try_page(
+        mut self,
+        id: impl Into<PageId>,
+        page: impl Into<El<'a, Message, R, E, S>>,
+    ) -> Result<Self, CapacityError> {
+        match collections::push::<_, N>(&mut self.pages, (id.into(), page.into())) {
+            Ok(()) => Ok(self),
+            Err(_) => Err(CapacityError { capacity: N }),
+        }
+    }
+
+    fn index_of(&self, id: PageId) -> Option<usize> {
+        self.pages.iter().position(|(page, _)| *page == id)
+    }
+
+    fn sync(&self, state: &mut StateNode) -> usize {
+        let current = state
+            .get::<RouterState>()
+            .stack
+            .last()
+            .and_then(|&(page, _)| self.index_of(page))
+            .unwrap_or(0);
+
+        self.current.set(current);
+        current
+    }
+
+    fn navigate(
+        &self,
+        ctx: &mut UiCtx<Message>,
+        navigation: Navigation,
+        state: &mut StateNode,
+        layout: Layout,
+    ) -> ActionResult {
+        let first = match self.pages.first() {
+            Some(&(first, _)) => first,
+            None => return ActionResult::Ignored,
+        };
+
+        let stack = &mut state.get_mut::<RouterState>().stack;
+        if stack.is_empty() {
+            stack.push((first, None));
+        }
+
+        let focus = match navigation {
+            Navigation::Push(page) | Navigation::Replace(page) => {
+                let Some(index) = self.index_of(page) else {
+                    return ActionResult::Ignored;
+                };
+
+                if let Navigation::Push(_) = navigation {
+                    stack.last_mut().unwrap().1 = ctx.focused();
+                } else {
+                    stack.pop();
+                }
+                stack.push((page, None));
+
+                self.pages[index].1.tree_ids().first().copied()
+            },
+            Navigation::Pop if stack.len() > 1 => {
+                stack.pop();
+
+                let &(page, focus) = stack.last().unwrap();
+                focus.or_else(|| {
+                    self.index_of(page)
+                        .and_then(|index| self.pages[index].1.tree_ids().first().copied())
+                })
+            },
+            Navigation::Pop => return ActionResult::Ignored,
+        };
+
+        self.sync(state);
+
+        if let Some(focus) = focus {
+            ctx.focus(focus);
+        }
+        ctx.invalidate_layout();
+        ctx.mark_dirty(layout.bounds());
+
+        ActionResult::Done
+    }
+}
+
+impl<'a, Message, R: Renderer, E: Event, S> Default for Router<'a, Message, R, E, S> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a, Message, R: Renderer, E: Event, S, const N: usize> Widget<Message, R, E, S>
+    for Router<'a, Message, R, E, S, N>
+{
+    fn id(&self) -> Option<ElId> {
+        None
+    }
+
+    fn tree_ids(&self) -> Vec<ElId> {
+        self.pages.get(self.current.get()).map(|(_, page)| page.tree_ids()).unwrap_or_default()
+    }
+
+    fn focus_bounds(
+        &self,
+        state: &mut StateNode,
+        layout: Layout,
+        bounds: &mut Vec<(ElId, Rectangle)>,
+    ) {
+        let current = self.current.get();
+
+        if let Some((_, page)) = self.pages.get(current) {
+            page.focus_bounds(&mut state.children[current], layout.first_child(), bounds);
+        }
+    }
+
+    fn debug(
+        &self,
+        state: &mut StateNode,
+        layout: Layout,
+        viewport: &Viewport,
+    ) -> Option<WidgetDebug<R::Color>> {
+        let debug = WidgetDebug::new(self, state, layout.clone(), viewport);
+        let current = self.current.get();
+        let page = self.pages.get(current).and_then(|(_, page)| {
+            page.debug(&mut state.children[current], layout.first_child(), viewport)
+        });
+
+        Some(debug.children([page]))
+    }
+
+    fn size(&self, viewport: &Viewport) -> Size<Length> {
+        self.pages
+            .get(self.current.get())
+            .map(|(_, page)| page.size(viewport))
+            .unwrap_or(Size::fill())
+    }
+
+    fn state_tag(&self) -> StateTag {
+        StateTag::of::<RouterState>()
+    }
+
+    fn state(&self) -> State {
+        State::new(RouterState::default())
+    }
+
+    /// Page states are keyed by page ids, so they are kept if pages are
+    /// reordered
+    fn state_children(&self) -> Vec<StateNode> {
+        self.pages
+            .iter()
+            .map(|(id, page)| StateNode { key: Some(StateKey::Page(*id)), ..StateNode::new(page) })
+            .collect()
+    }
+
+    fn layout(
+        &self,
+        ctx: &mut UiCtx<Message>,
+        state: &mut StateNode,
+        styler: &S,
+        limits: &Limits,
+        viewport: &Viewport,
+    ) -> LayoutNode {
+        if self.pages.is_empty() {
+            return LayoutNode::childless(Size::zero());
+        }
+
+        let current = self.sync(state);
+        let page = self.pages[current].1.layout(
+            ctx,
+            &mut state.children[current],
+            styler,
+            limits,
+            viewport,
+        );
+
+        LayoutNode::with_children(page.size(), Padding::default(), [page])
+    }
+
+    fn draw(
+        &self,
+        ctx: &mut UiCtx<Message>,
+        state: &mut StateNode,
+        renderer: &mut R,
+        styler: &S,
+        layout: Layout,
+        viewport: &Viewport,
+    ) {
+        let current = self.current.get();
+
+        if let Some((_, page)) = self.pages.get(current) {
+            page.draw(
+                ctx,
+                &mut state.children[current],
+                renderer,
+                styler,
+                layout.first_child(),
+                viewport,
+            );
+        }
+    }
+
+    fn draw_overlay(
+        &self,
+        ctx: &mut UiCtx<Message>,
+        state: &mut StateNode,
+        renderer: &mut R,
+        styler: &S,
+        layout: Layout,
+        viewport: &Viewport,
+    ) {
+        let current = self.current.get();
+
+        if let Some((_, page)) = self.pages.get(current) {
+            page.draw_overlay(
+                ctx,
+                &mut state.children[current],
+                renderer,
+                styler,
+                layout.first_child(),
+                viewport,
+            );
+        }
+    }
+
+    fn on_event(
+        &mut self,
+        ctx: &mut UiCtx<Message>,
+        event: E,
+        state: &mut StateNode,
+        layout: Layout,
+    ) -> EventResponse<E> {
+        let current = self.current.get();
+
+        let Some((_, page)) = self.pages.get_mut(current) else {
+            return Propagate::Ignored.into();
+        };
+
+        match page.on_event(
+            ctx,
+            event.clone(),
+            &mut state.children[current],
+            layout.clone().first_child(),
+        )? {
+            Propagate::Ignored => match event.as_common() {
+                Some(CommonEvent::Back) => {
+                    match self.navigate(ctx, Navigation::Pop, state, layout) {
+                        ActionResult::Ignored => Propagate::Ignored.into(),
+                        _ => Capture::Captured.into(),
+                    }
+                },
+                _ => Propagate::Ignored.into(),
+            },
+            bubbled @ Propagate::BubbleUp(..) => bubbled.into(),
+        }
+    }
+
+    fn overlay_event(
+        &mut self,
+        ctx: &mut UiCtx<Message>,
+        event: E,
+        state: &mut StateNode,
+        layout: Layout,
+    ) -> Option<EventResponse<E>> {
+        let current = self.current.get();
+
+        self.pages.get_mut(current)?.1.overlay_event(
+            ctx,
+            event,
+            &mut state.children[current],
+            layout.first_child(),
+        )
+    }
+
+    fn perform(
+        &mut self,
+        ctx: &mut UiCtx<Message>,
+        action: &WidgetAction,
+        state: &mut StateNode,
+        layout: Layout,
+    ) -> ActionResult {
+        let current = self.current.get();
+
+        // Nested routers navigate first
+        if let Some((_, page)) = self.pages.get_mut(current) {
+            match page.perform(
+                ctx,
+                action,
+                &mut state.children[current],
+                layout.clone().first_child(),
+            ) {
+                ActionResult::Ignored => {},
+                result => return result,
+            }
+        }
+
+        match *action {
+            WidgetAction::Navigate(navigation) => self.navigate(ctx, navigation, state, layout),
+            _ => perform_default(self, ctx, action, state, layout),
+        }
+    }
+}
+
+impl<'a, Message, R, E, S, const N: usize> From<Router<'a, Message, R, E, S, N>>
+    for El<'a, Message, R, E, S>
+where
+    Message: 'a,
+    R: Renderer + 'a,
+    E: Event + 'a,
+    S: 'a,
+{
+    fn from(value: Router<'a, Message, R, E, S, N>) -> Self {
+        Self::new(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use crate::{
+        action::Action,
+        col,
+        event::CommonEvent,
+        helpers::{button, checkbox},
+        testing::{click, harness, Script},
+    };
+
+    use super::Router;
+
+    /// Pages with buttons publishing their numbers, the main one has two
+    fn pages() -> Script<u8> {
+        let router: Router<_, _, _, _> = Router::new()
+            .page("main", col![button("M1").on_press(1), button("M2").on_press(2)])
+            .page("a", button("A").on_press(3))
+            .page("b", button("B").on_press(4));
+
+        harness(router).auto_focus()
+    }
+
+    /// Message of the focused button
+    fn clicked(harness: Script<u8>) -> (Script<u8>, Vec<u8>) {
+        let mut harness = harness.events(click());
+        let messages = harness.messages();
+        (harness, messages)
+    }
+
+    fn perform(mut harness: Script<u8>, action: Action) -> Script<u8> {
+        harness.ui().perform(action);
+        harness.events([])
+    }
+
+    #[test]
+    fn pushes_pops_and_replaces_pages() {
+        let (harness, messages) = clicked(pages());
+        assert_eq!(messages, [1]);
+
+        let (harness, messages) = clicked(perform(harness, Action::push("a")));
+        assert_eq!(messages, [3]);
+
+        let (harness, messages) = clicked(perform(harness, Action::replace("b")));
+        assert_eq!(messages, [4]);
+
+        // Replaced page is not in history
+        let (harness, messages) = clicked(perform(harness, Action::pop()));
+        assert_eq!(messages, [1]);
+
+        // Unknown page is ignored
+        let (_, messages) = clicked(perform(harness, Action::push("none")));
+        assert_eq!(messages, [1]);
+    }
+
+    #[test]
+    fn goes_back_to_previous_page() {
+        // Nothing to go back to
+        let harness = pages().events([CommonEvent::Back.into()]);
+        let (harness, messages) = clicked(harness);
+        assert_eq!(messages, [1]);
+
+        let harness = perform(harness, Action::push("a")).events([CommonEvent::Back.into()]);
+        let (_, messages) = clicked(harness);
+        assert_eq!(messages, [1]);
+    }
+
+    #[test]
+    fn keeps_page_state_across_navigation() {
+        // Focus of the page is restored on coming back
+        let script = pages().events([CommonEvent::FocusMove(1).into()]);
+        let script = perform(perform(script, Action::push("a")), Action::pop());
+        let (_, messages) = clicked(script);
+        assert_eq!(messages, [2]);
+
+        // Widget states of hidden pages are kept
+        let router: Router<_, _, _, _> = Router::new()
+            .page("main", checkbox(|checked| checked as u8))
+            .page("a", button("A").on_press(3));
+        let (harness, messages) = clicked(harness(router).auto_focus());
+        assert_eq!(messages, [1]);
+
+        let harness = perform(perform(harness, Action::push("a")), Action::pop());
+        let (_, messages) = clicked(harness);
+        assert_eq!(messages, [0]);
+    }
+}
@@ -1,13 +1,16 @@
 use embedded_graphics::{
     geometry::{AnchorPoint, Point},
-    primitives::{Line, Primitive, PrimitiveStyle},
+    primitives::{Line, Primitive, PrimitiveStyle, Rectangle},
 };
 
 use crate::{
+    action::{ActionResult, WidgetAction},
+    animation::{Animated, Transition},
     axis::{Axial, Axis},
     block::{Block, BoxModel},
+    debug::WidgetDebug,
     el::{El, ElId},
-    event::{Capture, CommonEvent, Event, Propagate},
+    event::{Capture, CommonEvent, Event, EventResponse, Propagate},
     layout::{Layout, Limits, Viewport},
     padding::Padding,
     palette::PaletteColor,
@@ -16,9 +19,11 @@ use crate::{
     state::{State, StateNode, StateTag},
     style::component_style,
     theme::Theme,
-    widget::Widget,
+    widget::{perform_default, Widget},
 };
 
+pub use crate::event::ScrollEvent;
+
 struct Scrollbar<'a> {
     child_layout: Layout<'a>,
     max_offset: u32,
@@ -66,18 +71,22 @@ impl<'a> Scrollbar<'a> {
     }
 }
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Debug)]
 struct ScrollableState {
     // TODO: Solve how to interface with 2D scrollable with encoder and add 2D
     // offset: Size,
     offset: u32,
+    /// Offset content is drawn with, animated towards `offset`
+    shown_offset: Option<Animated<u32>>,
     pressed: bool,
     active: bool,
+    /// Pointer position on the axis and offset the drag started with
+    drag: Option<(i32, u32)>,
 }
 
 impl Default for ScrollableState {
     fn default() -> Self {
-        Self { offset: 0, pressed: false, active: false }
+        Self { offset: 0, shown_offset: None, pressed: false, active: false, drag: None }
     }
 }
 
@@ -167,6 +176,7 @@ where
     dir: ScrollDir,
     padding: Padding,
     always_show: bool,
+    transition: Transition,
     class: S::Class<'a>,
 }
 
@@ -194,6 +204,7 @@ where
             dir: ScrollDir::Vertical,
             padding: 1.into(),
             always_show: false,
+            transition: Transition::none(),
             class: S::default(),
         }
     }
@@ -202,12 +213,18 @@ where
         self.always_show = true;
         self
     }
+
+    /// Scroll smoothly
+    pub fn transition(mut self, transition: impl Into<Transition>) -> Self {
+        self.transition = transition.into();
+        self
+    }
 }
 
 impl<'a, Message, R, E, S> Widget<Message, R, E, S> for Scrollable<'a, Message, R, E, S>
 where
     R: Renderer,
-    E: Event,
+    E: ScrollEvent,
     S: ScrollableStyler<R::Color>,
 {
     fn id(&self) -> Option<ElId> {
@@ -220,6 +237,33 @@ where
         ids
     }
 
+    fn focus_bounds(
+        &self,
+        state: &mut StateNode,
+        layout: Layout,
+        bounds: &mut alloc::vec::Vec<(ElId, Rectangle)>,
+    ) {
+        bounds.push((self.id, layout.bounds()));
+
+        let offset = state.get::<ScrollableState>().offset;
+        let scrollbar = Scrollbar::new(self.axis, layout, offset);
+        self.content.focus_bounds(&mut state.children[0], scrollbar.child_layout, bounds)
+    }
+
+    fn debug(
+        &self,
+        state: &mut StateNode,
+        layout: Layout,
+        viewport: &Viewport,
+    ) -> Option<WidgetDebug<R::Color>> {
+        let debug = WidgetDebug::new(self, state, layout.clone(), viewport);
+        let offset = state.get::<ScrollableState>().offset;
+        let scrollbar = Scrollbar::new(self.axis, layout, offset);
+        let content = self.content.debug(&mut state.children[0], scrollbar.child_layout, viewport);
+
+        Some(debug.children([content]))
+    }
+
     fn size(&self, _viewport: &crate::layout::Viewport) -> Size<Length> {
         self.size
     }
@@ -245,11 +289,22 @@ where
     ) -> crate::event::EventResponse<E> {
         let focused = ctx.is_focused(self);
         let current_state = *state.get::<ScrollableState>();
+        let bounds = layout.bounds();
 
         let scrollbar = Scrollbar::new(self.axis, layout, current_state.offset);
 
-        let event = if focused && current_state.active {
-            // Child (content) receives events only if scrollable is focused
+        let pointer = matches!(
+            event.as_common(),
+            Some(
+                CommonEvent::PointerDown(_)
+                    | CommonEvent::PointerMove(_)
+                    | CommonEvent::PointerUp(_)
+            )
+        );
+
+        let event = if focused && current_state.active || pointer && current_state.drag.is_none() {
+            // Child (content) receives events only if scrollable is focused,
+            // pointer ones go to the element under the pointer
             let child_propagate = self.content.on_event(
                 ctx,
                 event.clone(),
@@ -270,6 +325,7 @@ where
                         + offset)
                         .clamp(0, scrollbar.max_offset as i32)
                         as u32;
+                    ctx.mark_dirty(bounds);
                     return Capture::Captured.into();
                 }
             }
@@ -282,11 +338,12 @@ where
         // TODO: Generalized logic for focus+click components
         if let Some(common) = event.as_common() {
             match common {
-                CommonEvent::FocusMove(_) if focused => {
+                CommonEvent::FocusMove(_) | CommonEvent::FocusDirection(_) if focused => {
                     return Propagate::BubbleUp(self.id, event).into()
                 },
                 CommonEvent::FocusButtonDown if focused => {
                     state.get_mut::<ScrollableState>().pressed = true;
+                    ctx.mark_dirty(bounds);
                     return Capture::Captured.into();
                 },
                 CommonEvent::FocusButtonUp if focused => {
@@ -298,14 +355,47 @@ where
 
                     if was_pressed {
                         state_mut.active = !current_state.active;
+                        ctx.mark_dirty(bounds);
+                        return Capture::Captured.into();
+                    }
+                },
+                CommonEvent::PointerDown(point) if focused && bounds.contains(point) => {
+                    state.get_mut::<ScrollableState>().drag =
+                        Some((point.main_for(self.axis), current_state.offset));
+                    return Capture::Captured.into();
+                },
+                CommonEvent::PointerMove(point) => {
+                    if let Some((start, start_offset)) = current_state.drag {
+                        // Content follows the pointer without transition
+                        let offset = (start_offset as i32 - (point.main_for(self.axis) - start))
+                            .clamp(0, scrollbar.max_offset as i32)
+                            as u32;
+
+                        let state = state.get_mut::<ScrollableState>();
+                        state.offset = offset;
+                        state.shown_offset = Some(Animated::new(offset));
+                        ctx.mark_dirty(bounds);
                         return Capture::Captured.into();
                     }
                 },
+                CommonEvent::PointerUp(_) if current_state.drag.is_some() => {
+                    state.get_mut::<ScrollableState>().drag = None;
+                    return Capture::Captured.into();
+                },
+                CommonEvent::PointerDown(_)
+                | CommonEvent::PointerUp(_)
+                | CommonEvent::Gesture(_)
+                | CommonEvent::ToggleDebug => {},
                 CommonEvent::FocusButtonDown
                 | CommonEvent::FocusButtonUp
                 | CommonEvent::FocusMove(_)
-                | CommonEvent::Exit => {
-                    state.state.reset::<ScrollableState>();
+                | CommonEvent::FocusDirection(_)
+                | CommonEvent::Exit
+                | CommonEvent::Back => {
+                    if current_state.pressed || current_state.active || current_state.offset != 0 {
+                        state.state.reset::<ScrollableState>();
+                        ctx.mark_dirty(bounds);
+                    }
                 },
             }
         }
@@ -313,6 +403,53 @@ where
         Propagate::Ignored.into()
     }
 
+    fn perform(
+        &mut self,
+        ctx: &mut crate::ui::UiCtx<Message>,
+        action: &WidgetAction,
+        state: &mut StateNode,
+        layout: Layout,
+    ) -> ActionResult {
+        match *action {
+            WidgetAction::Activate(id) | WidgetAction::Deactivate(id) if id == self.id => {
+                state.get_mut::<ScrollableState>().active =
+                    matches!(action, WidgetAction::Activate(_));
+                ctx.mark_dirty(layout.bounds());
+                return ActionResult::Done;
+            },
+            _ => {},
+        }
+
+        let offset = state.get::<ScrollableState>().offset;
+        let bounds = layout.bounds();
+        let scrollbar = Scrollbar::new(self.axis, layout.clone(), offset);
+        let content_start = scrollbar.child_layout.bounds().top_left.main_for(self.axis);
+
+        match self.content.perform(ctx, action, &mut state.children[0], scrollbar.child_layout) {
+            ActionResult::Found(target) => {
+                // Scroll the least needed to show the whole target
+                let start = (target.top_left.main_for(self.axis) - content_start).max(0) as u32;
+                let end = start + target.size.main_for(self.axis);
+                let visible = bounds.size.main_for(self.axis);
+
+                let offset = if start < offset {
+                    start
+                } else if end > offset + visible {
+                    end - visible
+                } else {
+                    offset
+                };
+
+                state.get_mut::<ScrollableState>().offset = offset.min(scrollbar.max_offset);
+                ctx.mark_dirty(bounds);
+
+                ActionResult::Done
+            },
+            ActionResult::Ignored => perform_default(self, ctx, action, state, layout),
+            result => result,
+        }
+    }
+
     fn layout(
         &self,
         ctx: &mut crate::ui::UiCtx<Message>,
@@ -346,7 +483,13 @@ where
         viewport: &crate::layout::Viewport,
     ) {
         let bounds = layout.bounds();
-        let state = state_tree.get::<ScrollableState>();
+        let state = *state_tree.get::<ScrollableState>();
+        let offset = ctx.animate(
+            &mut state_tree.get_mut::<ScrollableState>().shown_offset,
+            state.offset,
+            self.transition,
+            bounds,
+        );
         let style = styler.style(
             &self.class,
             ScrollableStatus {
@@ -356,7 +499,7 @@ where
             },
         );
 
-        let scrollbar = Scrollbar::new(self.axis, layout, state.offset);
+        let scrollbar = Scrollbar::new(self.axis, layout, offset);
 
         renderer.block(Block::new_background(bounds, style.background));
 
@@ -385,16 +528,104 @@ where
 
         renderer.block(style.outline.into_outline(bounds));
     }
+
+    fn draw_overlay(
+        &self,
+        ctx: &mut crate::ui::UiCtx<Message>,
+        state_tree: &mut StateNode,
+        renderer: &mut R,
+        styler: &S,
+        layout: Layout,
+        viewport: &Viewport,
+    ) {
+        // Overlays stick to the content as it is drawn, mid-animation too
+        let bounds = layout.bounds();
+        let target = state_tree.get::<ScrollableState>().offset;
+        let offset = ctx.animate(
+            &mut state_tree.get_mut::<ScrollableState>().shown_offset,
+            target,
+            self.transition,
+            bounds,
+        );
+
+        self.content.draw_overlay(
+            ctx,
+            &mut state_tree.children[0],
+            renderer,
+            styler,
+            Scrollbar::new(self.axis, layout, offset).child_layout,
+            viewport,
+        );
+    }
+
+    fn overlay_event(
+        &mut self,
+        ctx: &mut crate::ui::UiCtx<Message>,
+        event: E,
+        state: &mut StateNode,
+        layout: Layout,
+    ) -> Option<EventResponse<E>> {
+        let offset = state.get::<ScrollableState>().offset;
+        let scrollbar = Scrollbar::new(self.axis, layout, offset);
+
+        self.content.overlay_event(ctx, event, &mut state.children[0], scrollbar.child_layout)
+    }
 }
 
 impl<'a, Message, R, E, S> From<Scrollable<'a, Message, R, E, S>> for El<'a, Message, R, E, S>
 where
     Message: 'a,
     R: Renderer + 'a,
-    E: Event + 'a,
+    E: ScrollEvent + 'a,
     S: ScrollableStyler<R::Color> + 'a,
 {
     fn from(value: Scrollable<'a, Message, R, E, S>) -> Self {
         El::new(value)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use alloc::string::{String, ToString};
+    use embedded_graphics::geometry::Point;
+
+    use crate::{
+        col,
+        event::CommonEvent,
+        helpers::scrollable_v,
+        size::Length,
+        testing::{harness, Script},
+    };
+
+    /// Offset of the scrollable from the tree dump
+    fn offset(script: &mut Script) -> String {
+        let dump = script.ui().dump_tree().to_string();
+        let start = dump.find("offset: ").unwrap();
+        dump[start..].split(',').next().unwrap().into()
+    }
+
+    #[test]
+    fn drags_content_with_pointer() {
+        let content =
+            col!["1", "2", "3", "4", "5", "6", "7", "8", "9", "10"].height(Length::Shrink);
+        let at = |y| Point::new(32, y);
+
+        let mut script = harness(scrollable_v(content)).events([
+            CommonEvent::PointerDown(at(20)).into(),
+            CommonEvent::PointerMove(at(10)).into(),
+        ]);
+        assert_eq!(offset(&mut script), "offset: 10");
+
+        // Dragging down past the start stops at it
+        let mut script = script.events([CommonEvent::PointerMove(at(25)).into()]);
+        assert_eq!(offset(&mut script), "offset: 0");
+
+        // Released content doesn't follow the pointer
+        let mut script = script.events([
+            CommonEvent::PointerMove(at(0)).into(),
+            CommonEvent::PointerUp(at(0)).into(),
+            CommonEvent::PointerMove(at(20)).into(),
+        ]);
+        assert_eq!(offset(&mut script), "offset: 20");
+    }
+}
@@ -5,10 +5,12 @@ use embedded_graphics::{geometry::Point, mono_font::MonoTextStyleBuilder, transf
 use embedded_text::{style::TextBoxStyleBuilder, TextBox};
 
 use crate::{
+    acceleration::{Acceleration, Velocity},
+    action::{ActionResult, WidgetAction},
     axis::{Axial, Axis},
     block::BoxModel,
     el::{El, ElId},
-    event::{Capture, CommonEvent, Event, Propagate},
+    event::{Capture, CommonEvent, Propagate},
     font::{Font, FontSize},
     icons::IconKind,
     layout::{Layout, LayoutNode, Viewport},
@@ -20,19 +22,23 @@ use crate::{
     style::component_style,
     theme::Theme,
     ui::UiCtx,
-    widget::Widget,
+    widget::{perform_default, Widget},
 };
 
 use super::icon::{Icon, IconStyler};
 
+pub use crate::event::SelectEvent;
+
+#[derive(Clone, Copy, Debug)]
 pub struct SelectState {
     is_pressed: bool,
     is_active: bool,
+    velocity: Velocity,
 }
 
 impl Default for SelectState {
     fn default() -> Self {
-        Self { is_pressed: false, is_active: false }
+        Self { is_pressed: false, is_active: false, velocity: Velocity::default() }
     }
 }
 
@@ -117,6 +123,7 @@ where
     on_change: Option<Box<dyn Fn(&O) -> Message + 'a>>,
     class: S::Class<'a>,
     circular: bool,
+    acceleration: Option<Acceleration>,
     axis: Axis,
     font: Font,
     // TODO: Siblings before and after
@@ -143,6 +150,7 @@ where
             on_change: None,
             class: <S as SelectStyler<R::Color>>::default(),
             circular: false,
+            acceleration: None,
             axis,
             font: R::default_font(),
             show_siblings: 1,
@@ -185,6 +193,12 @@ where
         self
     }
 
+    /// Skip options faster on fast rotation
+    pub fn acceleration(mut self, acceleration: Acceleration) -> Self {
+        self.acceleration = Some(acceleration);
+        self
+    }
+
     pub fn icon_prev(mut self, icon_prev: IconKind) -> Self {
         self.icon_prev = icon_prev;
         self
@@ -257,8 +271,8 @@ where
         FontSize::Relative(1.0).to_real(viewport)
     }
 
-    fn status<E: Event>(&self, ctx: &UiCtx<Message>, state: &SelectState) -> SelectStatus {
-        let &SelectState { is_pressed: pressed, is_active: active } = state;
+    fn status<E: SelectEvent>(&self, ctx: &UiCtx<Message>, state: &SelectState) -> SelectStatus {
+        let &SelectState { is_pressed: pressed, is_active: active, .. } = state;
 
         SelectStatus { active, pressed, focused: ctx.is_focused::<R, E, S>(self) }
     }
@@ -267,7 +281,7 @@ where
 impl<'a, Message, R, E, S, O, L> Widget<Message, R, E, S> for Select<'a, Message, R, S, O, L>
 where
     R: Renderer,
-    E: Event,
+    E: SelectEvent,
     S: SelectStyler<R::Color> + IconStyler<R::Color>,
     O: ToString,
     L: Borrow<[O]>,
@@ -299,20 +313,44 @@ where
         vec![]
     }
 
+    fn perform(
+        &mut self,
+        ctx: &mut UiCtx<Message>,
+        action: &WidgetAction,
+        state: &mut StateNode,
+        layout: Layout,
+    ) -> ActionResult {
+        match *action {
+            WidgetAction::Activate(id) | WidgetAction::Deactivate(id) if id == self.id => {
+                state.get_mut::<SelectState>().is_active =
+                    matches!(action, WidgetAction::Activate(_));
+                ctx.mark_dirty(layout.bounds());
+                ActionResult::Done
+            },
+            _ => perform_default::<Message, R, E, S, _>(self, ctx, action, state, layout),
+        }
+    }
+
     fn on_event(
         &mut self,
         ctx: &mut crate::ui::UiCtx<Message>,
         event: E,
         state: &mut StateNode,
-        _layout: Layout,
+        layout: Layout,
     ) -> crate::event::EventResponse<E> {
         // // TODO: Think about need of passing events to children, is it safe?
 
         let focused = ctx.is_focused::<R, E, S>(self);
-        let current_state = state.get::<SelectState>();
+        let current_state = *state.get::<SelectState>();
 
         if let Some(offset) = event.as_select_shift() {
             if focused && current_state.is_active {
+                let offset = state.get_mut::<SelectState>().velocity.apply(
+                    self.acceleration,
+                    offset,
+                    ctx.now(),
+                );
+
                 let prev = self.chosen;
                 if self.circular {
                     let len = self.options.borrow().len() as i32;
@@ -322,8 +360,10 @@ where
                         .clamp(0, self.options.borrow().len() as i32 - 1)
                         as usize;
                 }
-                if let Some(on_change) = self.on_change.as_ref() {
-                    if prev != self.chosen {
+                if prev != self.chosen {
+                    ctx.mark_dirty(layout.bounds());
+
+                    if let Some(on_change) = self.on_change.as_ref() {
                         ctx.publish((on_change)(&self.options.borrow()[self.chosen]));
                     }
                 }
@@ -333,11 +373,12 @@ where
 
         if let Some(common) = event.as_common() {
             match common {
-                CommonEvent::FocusMove(_) if focused => {
+                CommonEvent::FocusMove(_) | CommonEvent::FocusDirection(_) if focused => {
                     return Propagate::BubbleUp(self.id, event).into()
                 },
                 CommonEvent::FocusButtonDown if focused => {
                     state.get_mut::<SelectState>().is_pressed = true;
+                    ctx.mark_dirty(layout.bounds());
                     return Capture::Captured.into();
                 },
                 CommonEvent::FocusButtonUp if focused => {
@@ -346,17 +387,29 @@ where
                     state.get_mut::<SelectState>().is_pressed = false;
 
                     if was_pressed {
+                        ctx.mark_dirty(layout.bounds());
                         state.get_mut::<SelectState>().is_active =
                             !state.get::<SelectState>().is_active;
                         return Capture::Captured.into();
                     }
                 },
+                // TODO: Choose options by tapping arrows
+                CommonEvent::PointerMove(_)
+                | CommonEvent::PointerUp(_)
+                | CommonEvent::Gesture(_)
+                | CommonEvent::ToggleDebug => {},
                 CommonEvent::FocusButtonDown
                 | CommonEvent::FocusButtonUp
                 | CommonEvent::FocusMove(_)
-                | CommonEvent::Exit => {
+                | CommonEvent::FocusDirection(_)
+                | CommonEvent::PointerDown(_)
+                | CommonEvent::Exit
+                | CommonEvent::Back => {
                     // Should we reset state on any event? Or only on common
-                    state.state.reset::<SelectState>();
+                    if current_state.is_pressed || current_state.is_active {
+                        state.state.reset::<SelectState>();
+                        ctx.mark_dirty(layout.bounds());
+                    }
                 },
             }
         }
@@ -544,7 +597,7 @@ impl<'a, Message, R, E, S, O, L> From<Select<'a, Message, R, S, O, L>> for El<'a
 where
     Message: Clone + 'a,
     R: Renderer + 'a,
-    E: Event + 'a,
+    E: SelectEvent + 'a,
     S: SelectStyler<R::Color> + IconStyler<R::Color> + 'a,
     O: 'a,
     O: ToString,
@@ -554,3 +607,50 @@ where
         El::new(value)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use core::time::Duration;
+
+    use crate::{
+        acceleration::Acceleration,
+        event::CommonEvent,
+        helpers::select_h,
+        testing::{click, harness, Event},
+    };
+
+    const OPTIONS: [u8; 10] = [0, 1, 2, 3, 4, 5, 6, 7, 8, 9];
+
+    fn shift(steps: usize) -> impl Iterator<Item = Event> {
+        core::iter::repeat_n(CommonEvent::FocusMove(1).into(), steps)
+    }
+
+    #[test]
+    fn accelerates_fast_shifts() {
+        let acceleration = Acceleration::new(Duration::from_millis(50), 3);
+        let mut script =
+            harness(select_h(OPTIONS).on_change(|&option| option).acceleration(acceleration))
+                .auto_focus()
+                .events(click())
+                .events(shift(3));
+        assert_eq!(script.messages(), [1, 3, 6]);
+
+        let mut script = script.advance(Duration::from_millis(50)).events(shift(1));
+        assert_eq!(script.messages(), [7]);
+    }
+
+    #[test]
+    fn wraps_accelerated_shifts_of_circular_select() {
+        let acceleration = Acceleration::new(Duration::from_millis(50), 3);
+        let mut script = harness(
+            select_h(OPTIONS).on_change(|&option| option).circular(true).acceleration(acceleration),
+        )
+        .auto_focus()
+        .events(click())
+        .events(shift(4));
+        assert_eq!(script.messages(), [1, 3, 6, 9]);
+
+        let mut script = script.events(shift(1));
+        assert_eq!(script.messages(), [2]);
+    }
+}
@@ -1,12 +1,17 @@
 use alloc::{boxed::Box, vec::Vec};
-use embedded_graphics::primitives::{Line, Primitive, PrimitiveStyleBuilder, Rectangle};
+use embedded_graphics::{
+    geometry::Point,
+    primitives::{Line, Primitive, PrimitiveStyleBuilder, Rectangle},
+};
 
 use crate::{
+    acceleration::{Acceleration, Velocity},
+    action::{ActionResult, WidgetAction},
     axis::{Axial, Axis},
     block::{Block, Border},
     color::UiColor,
     el::{El, ElId},
-    event::{Capture, CommonEvent, Event, Propagate},
+    event::{Capture, CommonEvent, Propagate},
     layout::{Layout, Viewport},
     palette::PaletteColor,
     render::Renderer,
@@ -15,18 +20,23 @@ use crate::{
     style::component_style,
     theme::Theme,
     ui::UiCtx,
-    widget::Widget,
+    widget::{perform_default, Widget},
 };
 
-#[derive(Clone, Copy)]
+pub use crate::event::SliderEvent;
+
+#[derive(Clone, Copy, Debug)]
 struct SliderState {
     active: bool,
     pressed: bool,
+    /// Shifted while pressed, so release doesn't toggle active
+    adjusted: bool,
+    velocity: Velocity,
 }
 
 impl Default for SliderState {
     fn default() -> Self {
-        Self { active: false, pressed: false }
+        Self { active: false, pressed: false, adjusted: false, velocity: Velocity::default() }
     }
 }
 
@@ -74,6 +84,8 @@ where
     size: Size<Length>,
     value: u8,
     step: u8,
+    fine_step: u8,
+    acceleration: Option<Acceleration>,
     // knob_icon: IconKind,
     on_change: Box<dyn Fn(SliderPosition) -> Message + 'a>,
     class: S::Class<'a>,
@@ -94,6 +106,8 @@ where
             size: Size::fill(),
             value: 0,
             step: 1,
+            fine_step: 1,
+            acceleration: None,
             on_change: Box::new(on_change),
             class: S::default(),
         }
@@ -128,9 +142,55 @@ where
         self
     }
 
+    /// Step of shift while the focus button is held, for fine adjustment
+    /// with coarse `step`
+    pub fn fine_step(mut self, fine_step: u8) -> Self {
+        self.fine_step = fine_step;
+        self
+    }
+
+    /// Speed up fast shifts of the active slider
+    pub fn acceleration(mut self, acceleration: Acceleration) -> Self {
+        self.acceleration = Some(acceleration);
+        self
+    }
+
     // Helpers //
-    fn status<E: Event>(&self, ctx: &UiCtx<Message>, state: &StateNode) -> SliderStatus {
-        let &SliderState { active, pressed } = state.get::<SliderState>();
+    fn shift(&mut self, ctx: &mut UiCtx<Message>, steps: i32, step: u8, layout: Layout) {
+        let prev_value = self.value;
+
+        self.value = (self.value as i32)
+            .saturating_add(steps.saturating_mul(step as i32))
+            .clamp(0, u8::MAX as i32) as u8;
+
+        if prev_value != self.value {
+            ctx.mark_dirty(layout.bounds());
+            ctx.publish((self.on_change)(self.value));
+        }
+    }
+
+    /// Move the knob to the pointer position along the guide
+    fn drag_to(&mut self, ctx: &mut UiCtx<Message>, point: Point, layout: Layout) {
+        let bounds = layout.bounds();
+        let length = bounds.size.main_for(self.axis);
+
+        if length == 0 {
+            return;
+        }
+
+        let shift = (point.main_for(self.axis) - bounds.top_left.main_for(self.axis))
+            .clamp(0, length as i32) as u32;
+        let value = (shift * u8::MAX as u32 / length) as u8;
+
+        if value != self.value {
+            self.value = value;
+            ctx.mark_dirty(bounds);
+            ctx.publish((self.on_change)(self.value));
+        }
+    }
+
+    fn status<E: SliderEvent>(&self, ctx: &UiCtx<Message>, state: &mut StateNode) -> SliderStatus {
+        let &SliderState { active, pressed, .. } = state.get::<SliderState>();
         let focused = UiCtx::is_focused::<R, E, S>(&ctx, self);
 
         SliderStatus { active, pressed, focused }
@@ -140,7 +200,7 @@ where
 impl<'a, Message, R, E, S> Widget<Message, R, E, S> for Slider<'a, Message, R, S>
 where
     R: Renderer,
-    E: Event,
+    E: SliderEvent,
     S: SliderStyler<R::Color>,
 {
     fn id(&self) -> Option<ElId> {
@@ -167,28 +227,48 @@ where
         vec![]
     }
 
+    fn perform(
+        &mut self,
+        ctx: &mut UiCtx<Message>,
+        action: &WidgetAction,
+        state: &mut StateNode,
+        layout: Layout,
+    ) -> ActionResult {
+        match *action {
+            WidgetAction::Activate(id) | WidgetAction::Deactivate(id) if id == self.id => {
+                state.get_mut::<SliderState>().active = matches!(action, WidgetAction::Activate(_));
+                ctx.mark_dirty(layout.bounds());
+                ActionResult::Done
+            },
+            _ => perform_default::<Message, R, E, S, _>(self, ctx, action, state, layout),
+        }
+    }
+
     fn on_event(
         &mut self,
         ctx: &mut crate::ui::UiCtx<Message>,
         event: E,
         state: &mut crate::state::StateNode,
-        _layout: Layout,
+        layout: Layout,
     ) -> crate::event::EventResponse<E> {
         let focused = ctx.is_focused::<R, E, S>(self);
         let current_state = *state.get::<SliderState>();
 
         if let Some(offset) = event.as_slider_shift() {
-            if current_state.active {
-                let prev_value = self.value;
-
-                self.value = (self.value as i32)
-                    .saturating_add(offset * self.step as i32)
-                    .clamp(0, u8::MAX as i32) as u8;
-
-                if prev_value != self.value {
-                    ctx.publish((self.on_change)(self.value));
-                }
+            // Shift while pressed is fine adjustment
+            if current_state.pressed {
+                state.get_mut::<SliderState>().adjusted = true;
+                self.shift(ctx, offset, self.fine_step, layout);
+                return Capture::Captured.into();
+            }
 
+            if current_state.active {
+                let offset = state.get_mut::<SliderState>().velocity.apply(
+                    self.acceleration,
+                    offset,
+                    ctx.now(),
+                );
+                self.shift(ctx, offset, self.step, layout);
                 return Capture::Captured.into();
             }
         }
@@ -196,27 +276,57 @@ where
         // TODO: Generalize this focus logic for button, select and slider, etc.
         if let Some(common) = event.as_common() {
             match common {
-                CommonEvent::FocusMove(_) if focused => {
+                CommonEvent::FocusMove(_) | CommonEvent::FocusDirection(_) if focused => {
                     return Propagate::BubbleUp(self.id, event).into()
                 },
                 CommonEvent::FocusButtonDown if focused => {
-                    state.get_mut::<SliderState>().pressed = true;
+                    let state = state.get_mut::<SliderState>();
+                    state.pressed = true;
+                    state.adjusted = false;
+                    ctx.mark_dirty(layout.bounds());
                     return Capture::Captured.into();
                 },
                 CommonEvent::FocusButtonUp if focused => {
                     state.get_mut::<SliderState>().pressed = false;
 
                     if current_state.pressed {
-                        state.get_mut::<SliderState>().active = !state.get::<SliderState>().active;
+                        ctx.mark_dirty(layout.bounds());
+                        if !current_state.adjusted {
+                            state.get_mut::<SliderState>().active = !current_state.active;
+                        }
                         return Capture::Captured.into();
                     }
                 },
+                CommonEvent::PointerDown(point) if focused && layout.bounds().contains(point) => {
+                    state.get_mut::<SliderState>().pressed = true;
+                    self.drag_to(ctx, point, layout);
+                    return Capture::Captured.into();
+                },
+                CommonEvent::PointerMove(point) if current_state.pressed => {
+                    self.drag_to(ctx, point, layout);
+                    return Capture::Captured.into();
+                },
+                CommonEvent::PointerUp(_) if current_state.pressed => {
+                    state.get_mut::<SliderState>().pressed = false;
+                    ctx.mark_dirty(layout.bounds());
+                    return Capture::Captured.into();
+                },
+                CommonEvent::PointerMove(_)
+                | CommonEvent::PointerUp(_)
+                | CommonEvent::Gesture(_)
+                | CommonEvent::ToggleDebug => {},
                 CommonEvent::FocusButtonDown
                 | CommonEvent::FocusButtonUp
                 | CommonEvent::FocusMove(_)
-                | CommonEvent::Exit => {
+                | CommonEvent::FocusDirection(_)
+                | CommonEvent::PointerDown(_)
+                | CommonEvent::Exit
+                | CommonEvent::Back => {
                     // Should we reset state on any event? Or only on common
-                    state.reset::<SliderState>();
+                    if current_state.pressed || current_state.active {
+                        state.reset::<SliderState>();
+                        ctx.mark_dirty(layout.bounds());
+                    }
                 },
             }
         }
@@ -309,10 +419,71 @@ impl<'a, Message, R, E, S> From<Slider<'a, Message, R, S>> for El<'a, Message, R
 where
     Message: Clone + 'a,
     R: Renderer + 'a,
-    E: Event + 'a,
+    E: SliderEvent + 'a,
     S: SliderStyler<R::Color> + 'a,
 {
     fn from(value: Slider<'a, Message, R, S>) -> Self {
         El::new(value)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use core::time::Duration;
+
+    use embedded_graphics::geometry::Point;
+
+    use crate::{
+        acceleration::Acceleration,
+        event::CommonEvent,
+        helpers::slider_h,
+        testing::{click, harness, Event},
+    };
+
+    fn shift(steps: usize) -> impl Iterator<Item = Event> {
+        core::iter::repeat_n(CommonEvent::FocusMove(1).into(), steps)
+    }
+
+    #[test]
+    fn follows_pointer_drag() {
+        let at = |x| Point::new(x, 16);
+        let mut script = harness(slider_h(|value| value)).events([
+            CommonEvent::PointerDown(at(16)).into(),
+            CommonEvent::PointerMove(at(32)).into(),
+            CommonEvent::PointerMove(at(80)).into(),
+            CommonEvent::PointerUp(at(80)).into(),
+        ]);
+        assert_eq!(script.messages(), [63, 127, 255]);
+
+        // Released slider doesn't follow the pointer
+        let mut script = script.events([CommonEvent::PointerMove(at(0)).into()]);
+        assert_eq!(script.messages(), []);
+    }
+
+    #[test]
+    fn shifts_by_fine_step_while_pressed() {
+        let mut script = harness(slider_h(|value| value).step(10).fine_step(1))
+            .auto_focus()
+            .events([CommonEvent::FocusButtonDown.into()])
+            .events(shift(2))
+            .events([CommonEvent::FocusButtonUp.into()]);
+        assert_eq!(script.messages(), [1, 2]);
+
+        // Adjusting release doesn't activate the slider
+        let mut script = script.events(shift(1));
+        assert_eq!(script.messages(), []);
+    }
+
+    #[test]
+    fn accelerates_fast_shifts() {
+        let acceleration = Acceleration::new(Duration::from_millis(50), 3);
+        let mut script = harness(slider_h(|value| value).step(10).acceleration(acceleration))
+            .auto_focus()
+            .events(click())
+            .events(shift(4));
+        assert_eq!(script.messages(), [10, 30, 60, 90]);
+
+        let mut script = script.advance(Duration::from_millis(50)).events(shift(1));
+        assert_eq!(script.messages(), [100]);
+    }
+}
@@ -1,5 +1,5 @@
 use alloc::{string::ToString as _, vec::Vec};
-use core::fmt::Display;
+use core::fmt::{self, Display, Write as _};
 
 use embedded_graphics::mono_font::{MonoTextStyle, MonoTextStyleBuilder};
 use embedded_text::{
@@ -16,7 +16,7 @@ use crate::{
     palette::PaletteColor,
     render::Renderer,
     size::{Length, Size},
-    state::StateNode,
+    state::{State, StateNode, StateTag},
     style::component_style,
     theme::Theme,
     ui::UiCtx,
@@ -95,6 +95,46 @@ impl Into<embedded_text::alignment::HorizontalAlignment> for TextAlign {
     }
 }
 
+/// Text measured on last layout. Hash of the content is compared to the
+/// text being drawn, so it's measured again only if content changed, and UI
+/// is relaid out only if its height changed.
+#[derive(Clone, Copy, Default)]
+struct TextState {
+    max_width: u32,
+    height: u32,
+    content: u64,
+}
+
+impl fmt::Debug for TextState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TextState")
+            .field("max_width", &self.max_width)
+            .field("height", &self.height)
+            .finish_non_exhaustive()
+    }
+}
+
+/// FNV-1a hash of formatted text, to compare content without allocating it
+struct ContentHash(u64);
+
+impl ContentHash {
+    fn of(content: &impl Display) -> u64 {
+        let mut hash = Self(0xcbf2_9ce4_8422_2325);
+        // Hasher never fails
+        let _ = write!(hash, "{content}");
+        hash.0
+    }
+}
+
+impl fmt::Write for ContentHash {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        for byte in s.bytes() {
+            self.0 = (self.0 ^ byte as u64).wrapping_mul(0x0100_0000_01b3);
+        }
+        Ok(())
+    }
+}
+
 pub struct Text<'a, T, R, S>
 where
     R: Renderer,
@@ -153,6 +193,7 @@ where
         self
     }
 
+    /// Change content. If its size changes, UI is relaid out on the next draw
     pub fn update(&mut self, new_value: T) {
         self.content = new_value;
     }
@@ -184,6 +225,14 @@ where
             .build()
     }
 
+    fn measure_height(&self, style: &TextStyle<R::Color>, viewport: &Viewport, width: u32) -> u32 {
+        self.textbox_style().measure_text_height(
+            &self.text_style(style, viewport),
+            &self.content.to_string(),
+            width,
+        )
+    }
+
     // fn compute_size(&self, text: &str, style: &TextStyle<R::Color>, viewport:
     // &Viewport) -> Size {     // self.font.to_real(viewport).
     // measure_text_size(&self.content.to_string())
@@ -210,10 +259,18 @@ where
         self.size
     }
 
+    fn state_tag(&self) -> StateTag {
+        StateTag::of::<TextState>()
+    }
+
+    fn state(&self) -> State {
+        State::new(TextState::default())
+    }
+
     fn layout(
         &self,
         _ctx: &mut UiCtx<Message>,
-        _state_tree: &mut StateNode,
+        state_tree: &mut StateNode,
         styler: &S,
         limits: &crate::layout::Limits,
         viewport: &Viewport,
@@ -226,11 +283,12 @@ where
             // and we cannot get what is the actual text width, so it takes all the width is
             // can. This can be seen when putting text into container and setting text
             // horizontal alignment to Center.
-            let text_height = self.textbox_style().measure_text_height(
-                &self.text_style(&style, viewport),
-                &self.content.to_string(),
-                limits.max().width,
-            );
+            let text_height = self.measure_height(&style, viewport, width);
+            *state_tree.get_mut::<TextState>() = TextState {
+                max_width: width,
+                height: text_height,
+                content: ContentHash::of(&self.content),
+            };
 
             limits.resolve_size(self.size.width, self.size.height, Size::new(width, text_height))
         })
@@ -238,8 +296,8 @@ where
 
     fn draw(
         &self,
-        _ctx: &mut UiCtx<Message>,
-        _state_tree: &mut StateNode,
+        ctx: &mut UiCtx<Message>,
+        state_tree: &mut StateNode,
         renderer: &mut R,
         styler: &S,
         layout: Layout,
@@ -247,6 +305,15 @@ where
     ) {
         let style = styler.style(&self.class, TextStatus::Normal);
 
+        let content = ContentHash::of(&self.content);
+        let state = state_tree.get_mut::<TextState>();
+        if content != state.content {
+            state.content = content;
+            if self.measure_height(&style, viewport, state.max_width) != state.height {
+                ctx.invalidate_layout();
+            }
+        }
+
         renderer.mono_text(TextBox::with_textbox_style(
             &self.content.to_string(),
             layout.bounds(),
@@ -344,3 +411,97 @@ where
 //     pub style: TextStyle<R::Color>,
 //     pub text: &'a str,
 // }
+
+#[cfg(test)]
+mod tests {
+    use alloc::{
+        rc::Rc,
+        string::{String, ToString},
+    };
+    use core::{cell::RefCell, fmt};
+
+    use crate::{
+        col,
+        testing::{harness, Script},
+    };
+
+    use super::Text;
+
+    /// Content changed outside of the view, without rebuilding it
+    #[derive(Clone, Default)]
+    struct Shared(Rc<RefCell<String>>);
+
+    impl Shared {
+        fn set(&self, text: &str) {
+            *self.0.borrow_mut() = text.into();
+        }
+    }
+
+    impl fmt::Display for Shared {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str(&self.0.borrow())
+        }
+    }
+
+    fn text(content: &Shared) -> Script {
+        harness(col![Text::new(content.clone())])
+    }
+
+    /// Dump line of the text
+    fn text_line(harness: &mut Script) -> String {
+        harness.ui().dump_tree().to_string().lines().nth(1).unwrap().trim().into()
+    }
+
+    #[test]
+    fn relayouts_when_content_height_changes() {
+        let content = Shared::default();
+        content.set("Short");
+        let mut harness = text(&content);
+        assert_eq!(
+            text_line(&mut harness),
+            "Text 0,0 64x6 [shrink shrink] <TextState> TextState { max_width: 64, height: 6, .. }"
+        );
+
+        // Same height, layout is kept
+        content.set("Other");
+        harness.ui().redraw();
+        let mut harness = harness.events([]);
+        assert!(text_line(&mut harness).starts_with("Text 0,0 64x6 "));
+
+        content.set("Text long enough to wrap");
+        harness.ui().redraw();
+        let mut harness = harness.events([]);
+        assert_eq!(
+            text_line(&mut harness),
+            "Text 0,0 64x12 [shrink shrink] <TextState> TextState { max_width: 64, height: 12, .. }"
+        );
+    }
+
+    #[test]
+    fn relayouts_when_invalidated() {
+        let content = Shared::default();
+        content.set("Short");
+        let mut harness = text(&content);
+
+        // Not drawn, so the change is not noticed
+        content.set("Text long enough to wrap");
+        assert!(text_line(&mut harness).starts_with("Text 0,0 64x6 "));
+
+        harness.ui().invalidate_layout();
+        assert!(text_line(&mut harness).starts_with("Text 0,0 64x12 "));
+    }
+
+    #[test]
+    fn remeasures_on_resize() {
+        let content = Shared::default();
+        content.set("Text long enough to wrap");
+        let mut harness = text(&content);
+        assert!(text_line(&mut harness).starts_with("Text 0,0 64x12 "));
+
+        harness.ui().resize(crate::size::Size::new(32, 32));
+        assert_eq!(
+            text_line(&mut harness),
+            "Text 0,0 32x24 [shrink shrink] <TextState> TextState { max_width: 32, height: 24, .. }"
+        );
+    }
+}